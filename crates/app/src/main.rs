@@ -1,9 +1,294 @@
 use std::error::Error;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use domain::{DomainError, EntryFilter, EntryRepository};
+
+mod logger;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let db_path = std::env::current_dir()?.join("tui-money.db");
-    let repo = storage::SqliteRepository::new(db_path)?;
+    let args = Args::parse(std::env::args().skip(1))?;
+    if let Some(path) = &args.log_file {
+        logger::init(path)?;
+    }
+
+    // Checked before the TUI starts so a typo is reported right away.
+    let config = match dirs::config_dir() {
+        Some(dir) => load_config(&dir.join("tui-money").join("config.toml"))?,
+        None => ui::Config::default(),
+    };
+    let currency = match std::env::var("TUI_MONEY_CURRENCY")
+        .ok()
+        .filter(|code| !code.trim().is_empty())
+    {
+        Some(code) => domain::resolve_currency(Some(&code))
+            .map_err(|err| format!("TUI_MONEY_CURRENCY: {err}"))?,
+        None => config.currency,
+    };
 
-    ui::run(Box::new(repo))?;
+    let db_path = resolve_db_path(
+        args.db,
+        std::env::var("TUI_MONEY_DB").ok(),
+        &std::env::current_dir()?,
+    );
+    if let Some(parent) = db_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| format!("cannot create {}: {err}", parent.display()))?;
+    }
+    // Opening also checks the file, so a corrupt database is reported here
+    // rather than after the terminal has switched to the TUI.
+    let repo = storage::SqliteRepository::new(&db_path)
+        .map_err(|err| format!("cannot open {}: {err}", db_path.display()))?
+        .with_default_currency(currency);
+
+    if let Some(export) = &args.export {
+        // Headless: no terminal setup, just the file (or stdout).
+        return Ok(run_export(&repo, export)?);
+    }
+    ui::run(Box::new(repo), config)?;
     Ok(())
 }
+
+/// Loads the config file, printing warnings (such as unknown keys) before
+/// the TUI takes over the terminal.
+fn load_config(path: &Path) -> Result<ui::Config, String> {
+    let (config, warnings) =
+        ui::Config::load(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    for warning in warnings {
+        log::warn!("{}: {warning}", path.display());
+        eprintln!("warning: {}: {warning}", path.display());
+    }
+    Ok(config)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl ExportFormat {
+    fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            "md" | "markdown" => Ok(Self::Markdown),
+            _ => Err(format!(
+                "unknown export format: {name} (expected csv, json or markdown)"
+            )),
+        }
+    }
+}
+
+/// `tui-money export --format <format> --out <path>`; `-` writes to stdout.
+#[derive(Debug, PartialEq)]
+struct ExportArgs {
+    format: ExportFormat,
+    out: PathBuf,
+}
+
+/// Writes every entry to `export.out` in the chosen format.
+fn run_export(repo: &dyn EntryRepository, export: &ExportArgs) -> Result<(), DomainError> {
+    let out: Box<dyn Write> = if export.out == Path::new("-") {
+        Box::new(io::stdout().lock())
+    } else {
+        let file = File::create(&export.out).map_err(|err| {
+            DomainError::Storage(format!("cannot write {}: {err}", export.out.display()))
+        })?;
+        Box::new(io::BufWriter::new(file))
+    };
+    let filter = EntryFilter::default();
+    match export.format {
+        ExportFormat::Csv => storage::export_csv(repo, filter, out),
+        ExportFormat::Json => storage::export_json(repo, filter, out),
+        ExportFormat::Markdown => storage::export_markdown(repo, filter, out),
+    }
+}
+
+/// Command-line options; each takes a value as `--name <value>` or
+/// `--name=<value>`. The `export` subcommand runs without the TUI.
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    log_file: Option<PathBuf>,
+    db: Option<PathBuf>,
+    export: Option<ExportArgs>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let (mut log_file, mut db, mut format, mut out) = (None, None, None, None);
+        let mut export = false;
+        while let Some(arg) = args.next() {
+            if arg == "export" && !export {
+                export = true;
+                continue;
+            }
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            let slot = match name {
+                "--log-file" => &mut log_file,
+                "--db" => &mut db,
+                "--format" if export => &mut format,
+                "--out" if export => &mut out,
+                _ => return Err(format!("unknown argument: {arg}")),
+            };
+            let value = inline
+                .or_else(|| args.next())
+                .filter(|value| !value.is_empty())
+                .ok_or(format!("{name} needs a value"))?;
+            *slot = Some(value);
+        }
+
+        let export = if export {
+            Some(ExportArgs {
+                format: ExportFormat::from_name(format.as_deref().unwrap_or("csv"))?,
+                out: out
+                    .map(PathBuf::from)
+                    .ok_or("export needs --out <path> (or - for stdout)")?,
+            })
+        } else {
+            None
+        };
+        Ok(Self {
+            log_file: log_file.map(PathBuf::from),
+            db: db.map(PathBuf::from),
+            export,
+        })
+    }
+}
+
+/// Picks the database file: `--db` wins over `TUI_MONEY_DB`, which wins
+/// over `tui-money.db` in the working directory.
+fn resolve_db_path(flag: Option<PathBuf>, env: Option<String>, cwd: &Path) -> PathBuf {
+    flag.or_else(|| env.filter(|value| !value.is_empty()).map(PathBuf::from))
+        .unwrap_or_else(|| cwd.join("tui-money.db"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn db_path_prefers_flag_then_env_then_default() {
+        let cwd = Path::new("/work");
+        let flag = Some(PathBuf::from("/data/flag.db"));
+        let env = Some("/data/env.db".to_string());
+
+        assert_eq!(
+            resolve_db_path(flag, env.clone(), cwd),
+            PathBuf::from("/data/flag.db")
+        );
+        assert_eq!(
+            resolve_db_path(None, env, cwd),
+            PathBuf::from("/data/env.db")
+        );
+        assert_eq!(
+            resolve_db_path(None, Some(String::new()), cwd),
+            PathBuf::from("/work/tui-money.db")
+        );
+        assert_eq!(
+            resolve_db_path(None, None, cwd),
+            PathBuf::from("/work/tui-money.db")
+        );
+    }
+
+    #[test]
+    fn parses_flags_with_separate_or_inline_values() {
+        let args = parse(&["--db", "ledger.db", "--log-file=money.log"]).expect("parsed");
+        assert_eq!(
+            args,
+            Args {
+                log_file: Some(PathBuf::from("money.log")),
+                db: Some(PathBuf::from("ledger.db")),
+                export: None,
+            }
+        );
+
+        assert_eq!(parse(&["--db"]), Err("--db needs a value".to_string()));
+        assert_eq!(parse(&["--db="]), Err("--db needs a value".to_string()));
+        assert_eq!(
+            parse(&["--verbose"]),
+            Err("unknown argument: --verbose".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_the_export_subcommand() {
+        let args =
+            parse(&["--db=ledger.db", "export", "--format", "JSON", "--out", "-"]).expect("parsed");
+        assert_eq!(
+            args.export,
+            Some(ExportArgs {
+                format: ExportFormat::Json,
+                out: PathBuf::from("-"),
+            })
+        );
+        assert_eq!(args.db, Some(PathBuf::from("ledger.db")));
+
+        let csv = parse(&["export", "--out=entries.csv"]).expect("parsed");
+        assert_eq!(
+            csv.export.map(|export| export.format),
+            Some(ExportFormat::Csv)
+        );
+
+        assert!(parse(&["export"]).is_err());
+        assert!(parse(&["export", "--format", "xml", "--out", "x"]).is_err());
+        assert_eq!(
+            parse(&["--format", "csv"]),
+            Err("unknown argument: --format".to_string())
+        );
+    }
+
+    #[test]
+    fn export_writes_entries_from_a_database_file() {
+        let dir = std::env::temp_dir().join(format!("tui-money-export-{}", std::process::id()));
+        let db_path = dir.join("ledger.db");
+        std::fs::create_dir_all(&dir).expect("temp dir");
+        let mut repo = storage::SqliteRepository::new(&db_path).expect("repo created");
+        storage::import_csv(
+            &mut repo,
+            "kind,amount_cents,category,note,occurred_on\n\
+             expense,1250,food,lunch,2024-01-20\n"
+                .as_bytes(),
+        )
+        .expect("imported");
+
+        let csv_path = dir.join("entries.csv");
+        let export = ExportArgs {
+            format: ExportFormat::Csv,
+            out: csv_path.clone(),
+        };
+        run_export(&repo, &export).expect("exported");
+        assert_eq!(
+            std::fs::read_to_string(&csv_path).expect("csv read"),
+            "id,kind,amount_cents,currency,category,note,occurred_on\n\
+             1,expense,1250,USD,food,lunch,2024-01-20\n"
+        );
+
+        let json_path = dir.join("entries.json");
+        let export = ExportArgs {
+            format: ExportFormat::Json,
+            out: json_path.clone(),
+        };
+        run_export(&repo, &export).expect("exported");
+        let json = std::fs::read_to_string(&json_path).expect("json read");
+        assert!(json.contains("\"category\": \"food\""));
+
+        let missing = ExportArgs {
+            format: ExportFormat::Csv,
+            out: dir.join("no-such-dir").join("entries.csv"),
+        };
+        assert!(run_export(&repo, &missing).is_err());
+
+        drop(repo);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
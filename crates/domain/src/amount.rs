@@ -0,0 +1,299 @@
+use crate::error::DomainError;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use rusty_money::{Money, iso};
+use std::str::FromStr;
+
+/// Evaluates an amount typed by the user, e.g. `12.50 + 3.25` or `40/4`.
+///
+/// Supports `+`, `-`, `*`, `/`, parentheses and decimal literals; the result
+/// is rounded half-up to the currency's minor unit.
+pub fn evaluate_amount(
+    input: &str,
+    currency: &'static iso::Currency,
+) -> Result<Money<'static, iso::Currency>, DomainError> {
+    let mut parser = ExprParser {
+        chars: input.chars().collect(),
+        pos: 0,
+        depth: 0,
+    };
+    if parser.peek().is_none() {
+        return Err(invalid("Amount is required"));
+    }
+
+    let value = parser.expr()?;
+    if parser.peek().is_some() {
+        return Err(invalid("Amount contains unsupported characters"));
+    }
+
+    let rounded =
+        value.round_dp_with_strategy(currency.exponent, RoundingStrategy::MidpointAwayFromZero);
+    Ok(Money::from_decimal(rounded, currency))
+}
+
+/// Parses a plain amount such as `12.50` or `1,234.99`.
+///
+/// Commas are only accepted as thousands separators, so `1,2,3` is rejected.
+/// Unlike [`evaluate_amount`] nothing is rounded: more decimal places than
+/// the currency's minor unit allows is an error, as are negative values.
+pub fn parse_amount(
+    input: &str,
+    currency: &'static iso::Currency,
+) -> Result<Money<'static, iso::Currency>, DomainError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(invalid("Amount is required"));
+    }
+    if input.starts_with('-') {
+        return Err(invalid("Amount cannot be negative"));
+    }
+
+    let (whole, fraction) = input.split_once('.').unwrap_or((input, ""));
+    let is_number = |part: &str| !part.is_empty() && part.chars().all(|ch| ch.is_ascii_digit());
+    let mut groups = whole.split(',');
+    let lead = groups.next().unwrap_or_default();
+    let grouped = whole.contains(',');
+    if !is_number(lead)
+        || (grouped && lead.len() > 3)
+        || !groups.all(|group| group.len() == 3 && is_number(group))
+        || !(fraction.is_empty() || is_number(fraction))
+    {
+        return Err(invalid("Amount must be a number like 1,234.56"));
+    }
+    if fraction.len() > currency.exponent as usize {
+        return Err(DomainError::InvalidData(format!(
+            "Amount can have at most {} decimal places",
+            currency.exponent
+        )));
+    }
+
+    let digits = input.replace(',', "");
+    let value = Decimal::from_str(&digits).map_err(|_| invalid("Amount is too large"))?;
+    Ok(Money::from_decimal(value, currency))
+}
+
+/// The amount in the currency's minor units (cents for USD, whole yen for
+/// JPY), rounded half away from zero; `None` when it does not fit an `i64`.
+/// Every conversion from `Money` to stored or summed cents goes through here.
+pub fn minor_units(money: &Money<'static, iso::Currency>) -> Option<i64> {
+    let scale = Decimal::from(10_i64.pow(money.currency().exponent));
+    money
+        .amount()
+        .checked_mul(scale)?
+        .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+        .to_i64()
+}
+
+fn invalid(message: &str) -> DomainError {
+    DomainError::InvalidData(message.to_string())
+}
+
+/// Deepest parenthesis nesting accepted, so the recursive descent cannot
+/// overflow the stack.
+const MAX_DEPTH: usize = 32;
+
+struct ExprParser {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl ExprParser {
+    /// The next character after any whitespace. Whitespace only separates
+    /// tokens, so `1 2` is two numbers rather than `12`.
+    fn peek(&mut self) -> Option<char> {
+        while self
+            .chars
+            .get(self.pos)
+            .is_some_and(|ch| ch.is_whitespace())
+        {
+            self.pos += 1;
+        }
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expr(&mut self) -> Result<Decimal, DomainError> {
+        let mut value = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            value = if op == '+' {
+                value.checked_add(rhs)
+            } else {
+                value.checked_sub(rhs)
+            }
+            .ok_or_else(|| invalid("Amount is too large"))?;
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<Decimal, DomainError> {
+        let mut value = self.factor()?;
+        while let Some(op @ ('*' | '/')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.factor()?;
+            value = if op == '*' {
+                value
+                    .checked_mul(rhs)
+                    .ok_or_else(|| invalid("Amount is too large"))?
+            } else {
+                if rhs.is_zero() {
+                    return Err(invalid("Cannot divide by zero"));
+                }
+                value
+                    .checked_div(rhs)
+                    .ok_or_else(|| invalid("Amount is too large"))?
+            };
+        }
+        Ok(value)
+    }
+
+    fn factor(&mut self) -> Result<Decimal, DomainError> {
+        let mut negate = false;
+        while self.peek() == Some('-') {
+            self.pos += 1;
+            negate = !negate;
+        }
+
+        let value = if self.peek() == Some('(') {
+            if self.depth == MAX_DEPTH {
+                return Err(invalid("Amount is nested too deeply"));
+            }
+            self.pos += 1;
+            self.depth += 1;
+            let value = self.expr()?;
+            if self.peek() != Some(')') {
+                return Err(invalid("Missing closing parenthesis"));
+            }
+            self.pos += 1;
+            self.depth -= 1;
+            value
+        } else {
+            self.number()?
+        };
+        Ok(if negate { -value } else { value })
+    }
+
+    fn number(&mut self) -> Result<Decimal, DomainError> {
+        let start = self.pos;
+        while matches!(self.chars.get(self.pos), Some(ch) if ch.is_ascii_digit() || *ch == '.') {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(invalid("Amount must be a number or expression"));
+        }
+        let literal: String = self.chars[start..self.pos].iter().collect();
+        Decimal::from_str(&literal).map_err(|_| invalid("Amount must be a number or expression"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd(input: &str) -> Money<'static, iso::Currency> {
+        Money::from_str(input, iso::USD).expect("valid amount")
+    }
+
+    #[test]
+    fn minor_units_follow_the_currency_exponent() {
+        assert_eq!(minor_units(&usd("12.34")), Some(1234));
+        assert_eq!(minor_units(&usd("-0.05")), Some(-5));
+        assert_eq!(minor_units(&Money::from_major(1500, iso::JPY)), Some(1500));
+    }
+
+    #[test]
+    fn minor_units_round_half_away_from_zero_and_check_overflow() {
+        let money = |value| Money::from_decimal(value, iso::USD);
+        assert_eq!(minor_units(&money(Decimal::new(125, 3))), Some(13));
+        assert_eq!(minor_units(&money(Decimal::new(-125, 3))), Some(-13));
+        assert_eq!(minor_units(&money(Decimal::new(1234, 2))), Some(1234));
+        assert_eq!(minor_units(&money(Decimal::MAX)), None);
+    }
+
+    #[test]
+    fn evaluates_each_operator() {
+        assert_eq!(
+            evaluate_amount("12.50 + 3.25", iso::USD).unwrap(),
+            usd("15.75")
+        );
+        assert_eq!(evaluate_amount("20 - 7.5", iso::USD).unwrap(), usd("12.50"));
+        assert_eq!(evaluate_amount("2.5 * 4", iso::USD).unwrap(), usd("10"));
+        assert_eq!(evaluate_amount("40/4", iso::USD).unwrap(), usd("10"));
+    }
+
+    #[test]
+    fn respects_precedence_and_parentheses() {
+        assert_eq!(evaluate_amount("2 + 3 * 4", iso::USD).unwrap(), usd("14"));
+        assert_eq!(evaluate_amount("(2 + 3) * 4", iso::USD).unwrap(), usd("20"));
+        assert_eq!(evaluate_amount("10 - -2", iso::USD).unwrap(), usd("12"));
+        assert_eq!(evaluate_amount(" ( 3 ) ", iso::USD).unwrap(), usd("3"));
+    }
+
+    #[test]
+    fn rounds_division_to_currency_precision() {
+        assert_eq!(evaluate_amount("10/3", iso::USD).unwrap(), usd("3.33"));
+        assert_eq!(evaluate_amount("2/3", iso::USD).unwrap(), usd("0.67"));
+        assert_eq!(
+            evaluate_amount("1000/3", iso::JPY).unwrap(),
+            Money::from_minor(333, iso::JPY)
+        );
+    }
+
+    #[test]
+    fn parses_plain_amounts() {
+        assert_eq!(parse_amount("12.50", iso::USD).unwrap(), usd("12.50"));
+        assert_eq!(parse_amount("1,234.99", iso::USD).unwrap(), usd("1234.99"));
+        assert_eq!(parse_amount(" 7 ", iso::USD).unwrap(), usd("7"));
+        assert_eq!(
+            parse_amount("12,345,678", iso::USD).unwrap(),
+            usd("12345678")
+        );
+    }
+
+    #[test]
+    fn parse_rejects_extra_places_and_garbage() {
+        for input in [
+            "12.999", "abc", "-5", "", "1.2.3", ".5", "12 $", "1,2,3", "1234,567", ",123",
+            "1,234,", "1.23,4",
+        ] {
+            assert!(
+                matches!(
+                    parse_amount(input, iso::USD),
+                    Err(DomainError::InvalidData(_))
+                ),
+                "{input:?} should be rejected"
+            );
+        }
+        assert!(parse_amount("100.5", iso::JPY).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for input in [
+            "", " ", "12 $ 3", "abc", "(1 + 2", "4 / 0", "1..2", "3 +", "1 2", "1.5 0",
+        ] {
+            assert!(
+                matches!(
+                    evaluate_amount(input, iso::USD),
+                    Err(DomainError::InvalidData(_))
+                ),
+                "{input:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn deep_input_is_rejected_without_overflowing() {
+        let minuses = format!("{}5", "-".repeat(100_000));
+        assert_eq!(evaluate_amount(&minuses, iso::USD).unwrap(), usd("5"));
+
+        let parens = format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000));
+        assert!(matches!(
+            evaluate_amount(&parens, iso::USD),
+            Err(DomainError::InvalidData(_))
+        ));
+        let nested = format!("{}1{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+        assert_eq!(evaluate_amount(&nested, iso::USD).unwrap(), usd("1"));
+    }
+}
@@ -0,0 +1,33 @@
+use rusty_money::iso;
+
+use crate::error::DomainError;
+
+/// Currency used when nothing else is configured.
+pub const DEFAULT_CURRENCY: &iso::Currency = iso::USD;
+
+/// Resolves a configured ISO-4217 code (case-insensitive) to a currency.
+/// A missing or blank value means [`DEFAULT_CURRENCY`].
+pub fn resolve_currency(code: Option<&str>) -> Result<&'static iso::Currency, DomainError> {
+    let Some(code) = code.map(str::trim).filter(|code| !code.is_empty()) else {
+        return Ok(DEFAULT_CURRENCY);
+    };
+    iso::find(&code.to_ascii_uppercase())
+        .ok_or_else(|| DomainError::InvalidData(format!("unknown currency code: {code}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_means_usd_and_codes_are_looked_up() {
+        assert_eq!(resolve_currency(None).unwrap(), iso::USD);
+        assert_eq!(resolve_currency(Some(" ")).unwrap(), iso::USD);
+        assert_eq!(resolve_currency(Some("EUR")).unwrap(), iso::EUR);
+        assert_eq!(resolve_currency(Some("jpy")).unwrap(), iso::JPY);
+        assert!(matches!(
+            resolve_currency(Some("XYZ")),
+            Err(DomainError::InvalidData(_))
+        ));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::error::DomainError;
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Resolves a date typed into a form against `today`.
+///
+/// Besides `YYYY-MM-DD` this accepts an empty string or `today`, `yesterday`,
+/// and `-N` for N days ago.
+pub fn parse_relative_date(input: &str, today: NaiveDate) -> Result<NaiveDate, DomainError> {
+    let input = input.trim();
+    let days_ago = match input.to_ascii_lowercase().as_str() {
+        "" | "today" => Some(0),
+        "yesterday" => Some(1),
+        other => other
+            .strip_prefix('-')
+            .and_then(|days| days.parse::<u64>().ok()),
+    };
+    if let Some(days) = days_ago {
+        return today
+            .checked_sub_days(Days::new(days))
+            .ok_or_else(|| DomainError::InvalidData("Date is out of range".to_string()));
+    }
+
+    NaiveDate::parse_from_str(input, DATE_FORMAT).map_err(|_| {
+        DomainError::InvalidData(
+            "Date must be YYYY-MM-DD, today, yesterday or -N (days ago)".to_string(),
+        )
+    })
+}
+
+/// First and last day of the month `offset` months away from `today`'s.
+pub fn month_bounds(today: NaiveDate, offset: i32) -> (NaiveDate, NaiveDate) {
+    let this_month = today.with_day(1).unwrap_or(today);
+    let months = Months::new(offset.unsigned_abs());
+    let first = if offset < 0 {
+        this_month.checked_sub_months(months)
+    } else {
+        this_month.checked_add_months(months)
+    }
+    .unwrap_or(this_month);
+    let last = first
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(first);
+    (first, last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("valid date")
+    }
+
+    #[test]
+    fn keywords_and_blank_resolve_against_today() {
+        let today = date(2024, 3, 1);
+        assert_eq!(parse_relative_date("", today).unwrap(), today);
+        assert_eq!(parse_relative_date(" Today ", today).unwrap(), today);
+        assert_eq!(
+            parse_relative_date("yesterday", today).unwrap(),
+            date(2024, 2, 29)
+        );
+    }
+
+    #[test]
+    fn negative_offsets_count_days_back() {
+        let today = date(2024, 3, 1);
+        assert_eq!(parse_relative_date("-1", today).unwrap(), date(2024, 2, 29));
+        assert_eq!(parse_relative_date("-7", today).unwrap(), date(2024, 2, 23));
+    }
+
+    #[test]
+    fn explicit_dates_and_garbage() {
+        let today = date(2024, 3, 1);
+        assert_eq!(
+            parse_relative_date("2023-12-31", today).unwrap(),
+            date(2023, 12, 31)
+        );
+        for input in ["tomorrow", "-x", "+3", "2024-13-01"] {
+            assert!(matches!(
+                parse_relative_date(input, today),
+                Err(DomainError::InvalidData(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn month_bounds_cover_whole_calendar_months() {
+        let today = date(2024, 3, 31);
+        assert_eq!(
+            month_bounds(today, 0),
+            (date(2024, 3, 1), date(2024, 3, 31))
+        );
+        assert_eq!(
+            month_bounds(today, -1),
+            (date(2024, 2, 1), date(2024, 2, 29))
+        );
+        assert_eq!(
+            month_bounds(today, -3),
+            (date(2023, 12, 1), date(2023, 12, 31))
+        );
+        assert_eq!(
+            month_bounds(today, 1),
+            (date(2024, 4, 1), date(2024, 4, 30))
+        );
+    }
+}
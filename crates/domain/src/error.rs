@@ -8,4 +8,6 @@ pub enum DomainError {
     NotFound,
     #[error("invalid data: {0}")]
     InvalidData(String),
+    #[error("conflict: {0}")]
+    Conflict(String),
 }
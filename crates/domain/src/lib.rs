@@ -1,12 +1,25 @@
+mod amount;
+mod currency;
+mod date;
 mod error;
+#[cfg(feature = "test-support")]
+mod mock;
 mod models;
 mod repository;
+#[cfg(feature = "serde")]
+mod serde_money;
 mod user;
 
+pub use amount::{evaluate_amount, minor_units, parse_amount};
+pub use currency::{DEFAULT_CURRENCY, resolve_currency};
+pub use date::{month_bounds, parse_relative_date};
 pub use error::DomainError;
-// Alias for backward compatibility if needed, or just rename usages
-pub use error::DomainError as RepoError;
+#[cfg(feature = "test-support")]
+pub use mock::MockRepository;
 
-pub use models::{Category, Entry, EntryFilter, EntryId, EntryKind, NewEntry};
+pub use models::{
+    Account, AccountId, Category, CategoryTotal, Entry, EntryFilter, EntryId, EntryKind, EntrySort,
+    Frequency, MonthlyTotals, NewEntry, NewEntryBuilder, RecurringEntry, Totals,
+};
 pub use repository::{EntryRepository, UserRepository};
-pub use user::User;
+pub use user::{MIN_PASSWORD_LEN, User, validate_password};
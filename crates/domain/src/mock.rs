@@ -0,0 +1,488 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, NaiveDate};
+use rusty_money::{Money, iso};
+
+use crate::amount::minor_units;
+use crate::error::DomainError;
+use crate::models::{
+    Account, AccountId, Category, CategoryTotal, Entry, EntryFilter, EntryId, EntryKind, EntrySort,
+    MonthlyTotals, NewEntry, RecurringEntry, Totals,
+};
+use crate::repository::{EntryRepository, UserRepository};
+use crate::user::{User, validate_password};
+
+/// In-memory repository for tests that should not depend on SQLite.
+///
+/// Every trait method records its name, so tests can check what a screen
+/// asked for with [`calls`](Self::calls) or [`was_called`](Self::was_called).
+/// [`fail_on`](Self::fail_on) makes the next call of a method return an error.
+/// Passwords are stored in plain text.
+#[derive(Debug, Default)]
+pub struct MockRepository {
+    entries: Vec<Entry>,
+    archived: HashSet<EntryId>,
+    /// Username to user and password.
+    users: HashMap<String, (User, String)>,
+    accounts: Vec<Account>,
+    /// Rules with the number of occurrences generated so far.
+    recurring: Vec<(RecurringEntry, u32)>,
+    next_id: i64,
+    calls: RefCell<Vec<&'static str>>,
+    failures: RefCell<HashMap<&'static str, DomainError>>,
+}
+
+impl MockRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts with canned entries; later `add`s get ids after the largest one.
+    pub fn with_entries(entries: Vec<Entry>) -> Self {
+        let next_id = entries.iter().map(|entry| entry.id.0).max().unwrap_or(0);
+        Self {
+            entries,
+            next_id,
+            ..Self::default()
+        }
+    }
+
+    /// Names of the trait methods called so far, in order.
+    pub fn calls(&self) -> Vec<&'static str> {
+        self.calls.borrow().clone()
+    }
+
+    pub fn was_called(&self, method: &str) -> bool {
+        self.calls.borrow().contains(&method)
+    }
+
+    /// Makes the next call of `method` fail with `error`; later calls succeed.
+    pub fn fail_on(&self, method: &'static str, error: DomainError) {
+        self.failures.borrow_mut().insert(method, error);
+    }
+
+    fn record(&self, method: &'static str) -> Result<(), DomainError> {
+        self.calls.borrow_mut().push(method);
+        match self.failures.borrow_mut().remove(method) {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    fn next_id(&mut self) -> i64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn insert(&mut self, entry: NewEntry) -> Entry {
+        let tags = normalize_tags(entry.tags);
+        let entry = Entry {
+            id: EntryId(self.next_id()),
+            kind: entry.kind,
+            amount: entry.amount,
+            category: entry.category,
+            note: entry.note,
+            occurred_on: entry.occurred_on,
+            account: entry.account,
+            tags,
+            cleared: false,
+            version: 1,
+        };
+        self.entries.push(entry.clone());
+        entry
+    }
+
+    fn matching(&self, filter: &EntryFilter) -> Vec<Entry> {
+        let mut entries: Vec<Entry> = self
+            .entries
+            .iter()
+            .filter(|entry| filter.include_archived || !self.archived.contains(&entry.id))
+            .filter(|entry| matches_filter(entry, filter))
+            .cloned()
+            .collect();
+        entries.sort_by(|a, b| match filter.sort {
+            EntrySort::DateDesc => (b.occurred_on, b.id).cmp(&(a.occurred_on, a.id)),
+            EntrySort::DateAsc => (a.occurred_on, a.id).cmp(&(b.occurred_on, b.id)),
+            EntrySort::AmountDesc => (cents(&b.amount), b.occurred_on, b.id).cmp(&(
+                cents(&a.amount),
+                a.occurred_on,
+                a.id,
+            )),
+            EntrySort::AmountAsc => cents(&a.amount)
+                .cmp(&cents(&b.amount))
+                .then((b.occurred_on, b.id).cmp(&(a.occurred_on, a.id))),
+        });
+        entries
+    }
+
+    /// `matching`, limited to the currency sums are reported in.
+    fn matching_in_default_currency(&self, filter: &EntryFilter) -> Vec<Entry> {
+        let mut entries = self.matching(filter);
+        entries.retain(|entry| entry.amount.currency() == self.default_currency());
+        entries
+    }
+}
+
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .into_iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Stored amounts always fit, as they do in the `INTEGER` column.
+fn cents(amount: &Money<'static, iso::Currency>) -> i64 {
+    minor_units(amount).unwrap_or_default()
+}
+
+fn matches_filter(entry: &Entry, filter: &EntryFilter) -> bool {
+    let amount = cents(&entry.amount);
+    filter.from.is_none_or(|from| entry.occurred_on >= from)
+        && filter.to.is_none_or(|to| entry.occurred_on <= to)
+        && filter
+            .category
+            .as_ref()
+            .is_none_or(|category| &entry.category == category)
+        && filter
+            .account
+            .is_none_or(|account| entry.account == Some(account))
+        && filter
+            .tag
+            .as_deref()
+            .is_none_or(|tag| entry.tags.iter().any(|t| t == tag.trim()))
+        && filter.kind.is_none_or(|kind| entry.kind == kind)
+        && !(filter.only_uncleared && entry.cleared)
+        && filter.min_cents.is_none_or(|min| amount >= min)
+        && filter.max_cents.is_none_or(|max| amount <= max)
+        && filter
+            .note_contains
+            .as_deref()
+            .filter(|needle| !needle.is_empty())
+            .is_none_or(|needle| {
+                entry
+                    .note
+                    .as_deref()
+                    .is_some_and(|note| note.to_lowercase().contains(&needle.to_lowercase()))
+            })
+}
+
+fn add_to(totals: &mut Totals, entry: &Entry) {
+    match entry.kind {
+        EntryKind::Income => totals.income_cents += cents(&entry.amount),
+        EntryKind::Expense => totals.expense_cents += cents(&entry.amount),
+        EntryKind::Transfer => {}
+    }
+}
+
+impl EntryRepository for MockRepository {
+    fn add(&mut self, entry: NewEntry) -> Result<Entry, DomainError> {
+        self.record("add")?;
+        Ok(self.insert(entry))
+    }
+
+    fn list(&self, filter: EntryFilter) -> Result<Vec<Entry>, DomainError> {
+        self.record("list")?;
+        let offset = filter.offset.unwrap_or(0) as usize;
+        let limit = filter.limit.map_or(usize::MAX, |limit| limit as usize);
+        Ok(self
+            .matching(&filter)
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .collect())
+    }
+
+    fn count(&self, filter: EntryFilter) -> Result<u64, DomainError> {
+        self.record("count")?;
+        Ok(self.matching(&filter).len() as u64)
+    }
+
+    fn top_entries(&self, filter: EntryFilter, n: usize) -> Result<Vec<Entry>, DomainError> {
+        self.record("top_entries")?;
+        let filter = EntryFilter {
+            kind: Some(EntryKind::Expense),
+            sort: EntrySort::AmountDesc,
+            ..filter
+        };
+        Ok(self.matching(&filter).into_iter().take(n).collect())
+    }
+
+    fn get_entry(&self, id: EntryId) -> Result<Entry, DomainError> {
+        self.record("get_entry")?;
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .cloned()
+            .ok_or(DomainError::NotFound)
+    }
+
+    fn update_entry(&mut self, entry: Entry) -> Result<Entry, DomainError> {
+        self.record("update_entry")?;
+        let stored = self
+            .entries
+            .iter_mut()
+            .find(|stored| stored.id == entry.id && !self.archived.contains(&stored.id))
+            .ok_or(DomainError::NotFound)?;
+        if stored.version != entry.version {
+            return Err(DomainError::Conflict(
+                "entry was changed elsewhere; reload and try again".to_string(),
+            ));
+        }
+        *stored = Entry {
+            tags: normalize_tags(entry.tags),
+            cleared: stored.cleared,
+            version: entry.version + 1,
+            ..entry
+        };
+        Ok(stored.clone())
+    }
+
+    fn set_cleared(&mut self, id: EntryId, cleared: bool) -> Result<(), DomainError> {
+        self.record("set_cleared")?;
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.id == id && !self.archived.contains(&entry.id))
+            .ok_or(DomainError::NotFound)?;
+        entry.cleared = cleared;
+        Ok(())
+    }
+
+    fn delete_entry(&mut self, id: EntryId) -> Result<(), DomainError> {
+        self.record("delete_entry")?;
+        let exists = self.entries.iter().any(|entry| entry.id == id);
+        if !exists || !self.archived.insert(id) {
+            return Err(DomainError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn restore_entry(&mut self, id: EntryId) -> Result<(), DomainError> {
+        self.record("restore_entry")?;
+        if !self.archived.remove(&id) {
+            return Err(DomainError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn totals(&self, filter: EntryFilter) -> Result<Totals, DomainError> {
+        self.record("totals")?;
+        let mut totals = Totals::default();
+        for entry in self.matching_in_default_currency(&filter) {
+            add_to(&mut totals, &entry);
+        }
+        Ok(totals)
+    }
+
+    fn monthly_summary(&self, year: i32) -> Result<Vec<MonthlyTotals>, DomainError> {
+        self.record("monthly_summary")?;
+        let mut months: Vec<MonthlyTotals> = (1..=12)
+            .map(|month| MonthlyTotals {
+                month,
+                ..MonthlyTotals::default()
+            })
+            .collect();
+        for entry in self.entries.iter().filter(|entry| {
+            entry.occurred_on.year() == year
+                && !self.archived.contains(&entry.id)
+                && entry.amount.currency() == self.default_currency()
+        }) {
+            let month = &mut months[entry.occurred_on.month0() as usize];
+            let mut totals = Totals::default();
+            add_to(&mut totals, entry);
+            month.income_cents += totals.income_cents;
+            month.expense_cents += totals.expense_cents;
+        }
+        Ok(months)
+    }
+
+    fn daily_expense_totals(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, i64)>, DomainError> {
+        self.record("daily_expense_totals")?;
+        let expenses = self.matching_in_default_currency(&EntryFilter {
+            from: Some(from),
+            to: Some(to),
+            kind: Some(EntryKind::Expense),
+            ..EntryFilter::default()
+        });
+        Ok(from
+            .iter_days()
+            .take_while(|day| *day <= to)
+            .map(|day| {
+                let total = expenses
+                    .iter()
+                    .filter(|entry| entry.occurred_on == day)
+                    .map(|entry| cents(&entry.amount))
+                    .sum();
+                (day, total)
+            })
+            .collect())
+    }
+
+    fn category_breakdown(&self, filter: EntryFilter) -> Result<Vec<CategoryTotal>, DomainError> {
+        self.record("category_breakdown")?;
+        let mut sums: Vec<CategoryTotal> = Vec::new();
+        for entry in self.matching_in_default_currency(&filter) {
+            match sums.iter_mut().find(|row| row.category == entry.category) {
+                Some(row) => row.total_cents += cents(&entry.amount),
+                None => sums.push(CategoryTotal {
+                    total_cents: cents(&entry.amount),
+                    category: entry.category,
+                }),
+            }
+        }
+        sums.sort_by(|a, b| {
+            b.total_cents
+                .cmp(&a.total_cents)
+                .then_with(|| a.category.as_str().cmp(b.category.as_str()))
+        });
+        Ok(sums)
+    }
+
+    fn categories(&self) -> Result<Vec<Category>, DomainError> {
+        self.record("categories")?;
+        let mut categories: Vec<Category> = self
+            .entries
+            .iter()
+            .filter(|entry| !self.archived.contains(&entry.id))
+            .map(|entry| entry.category.clone())
+            .collect();
+        categories.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        categories.dedup();
+        Ok(categories)
+    }
+
+    fn merge_categories(
+        &mut self,
+        sources: Vec<Category>,
+        target: Category,
+    ) -> Result<u64, DomainError> {
+        self.record("merge_categories")?;
+        let mut moved = 0;
+        for entry in &mut self.entries {
+            if entry.category != target && sources.contains(&entry.category) {
+                entry.category = target.clone();
+                entry.version += 1;
+                moved += 1;
+            }
+        }
+        Ok(moved)
+    }
+
+    fn create_account(&mut self, name: &str) -> Result<Account, DomainError> {
+        self.record("create_account")?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DomainError::InvalidData(
+                "Account name cannot be empty".to_string(),
+            ));
+        }
+        if self.accounts.iter().any(|account| account.name == name) {
+            return Err(DomainError::Conflict(
+                "account name already taken".to_string(),
+            ));
+        }
+        let account = Account {
+            id: AccountId(self.next_id()),
+            name: name.to_string(),
+        };
+        self.accounts.push(account.clone());
+        Ok(account)
+    }
+
+    fn list_accounts(&self) -> Result<Vec<Account>, DomainError> {
+        self.record("list_accounts")?;
+        let mut accounts = self.accounts.clone();
+        accounts.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(accounts)
+    }
+
+    fn add_recurring(&mut self, rule: RecurringEntry) -> Result<(), DomainError> {
+        self.record("add_recurring")?;
+        self.recurring.push((rule, 0));
+        Ok(())
+    }
+
+    fn expand_due(&mut self, as_of: NaiveDate) -> Result<Vec<Entry>, DomainError> {
+        self.record("expand_due")?;
+        let mut due = Vec::new();
+        for (rule, generated) in &mut self.recurring {
+            while let Some(date) = rule.occurrence(*generated).filter(|date| *date <= as_of) {
+                due.push(rule.to_new_entry(date));
+                *generated += 1;
+            }
+        }
+        Ok(due.into_iter().map(|entry| self.insert(entry)).collect())
+    }
+}
+
+impl UserRepository for MockRepository {
+    fn create_user(&mut self, username: &str, password: &str) -> Result<User, DomainError> {
+        self.record("create_user")?;
+        validate_password(password)?;
+        if self.users.contains_key(username) {
+            return Err(DomainError::Conflict("username already taken".to_string()));
+        }
+        let user = User {
+            id: self.next_id(),
+            username: username.to_string(),
+        };
+        self.users
+            .insert(username.to_string(), (user.clone(), password.to_string()));
+        Ok(user)
+    }
+
+    fn verify_user(&self, username: &str, password: &str) -> Result<Option<User>, DomainError> {
+        self.record("verify_user")?;
+        Ok(self
+            .users
+            .get(username)
+            .filter(|(_, stored)| stored == password)
+            .map(|(user, _)| user.clone()))
+    }
+
+    fn change_password(
+        &mut self,
+        username: &str,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<(), DomainError> {
+        self.record("change_password")?;
+        let (_, stored) = self.users.get_mut(username).ok_or(DomainError::NotFound)?;
+        if stored != old_password {
+            return Err(DomainError::InvalidData(
+                "Current password is incorrect".to_string(),
+            ));
+        }
+        validate_password(new_password)?;
+        *stored = new_password.to_string();
+        Ok(())
+    }
+
+    fn delete_user(&mut self, username: &str, password: &str) -> Result<(), DomainError> {
+        self.record("delete_user")?;
+        let (_, stored) = self.users.get(username).ok_or(DomainError::NotFound)?;
+        if stored != password {
+            return Err(DomainError::InvalidData(
+                "Password is incorrect".to_string(),
+            ));
+        }
+        self.users.remove(username);
+        Ok(())
+    }
+
+    fn list_users(&self) -> Result<Vec<String>, DomainError> {
+        self.record("list_users")?;
+        let mut users: Vec<String> = self.users.keys().cloned().collect();
+        users.sort();
+        Ok(users)
+    }
+}
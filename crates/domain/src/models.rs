@@ -1,8 +1,13 @@
 use crate::error::DomainError;
-use chrono::NaiveDate;
+use chrono::{Days, Local, Months, NaiveDate};
 use rusty_money::{Money, iso};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct EntryId(pub i64);
 
 impl std::fmt::Display for EntryId {
@@ -11,31 +16,90 @@ impl std::fmt::Display for EntryId {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct AccountId(pub i64);
+
+/// A place money lives in, such as a checking or savings account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+    pub id: AccountId,
+    pub name: String,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum EntryKind {
     Expense,
     Income,
+    /// Money moved between the user's own accounts; not counted in totals.
+    Transfer,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
 pub struct Category(String);
 
 impl Category {
+    pub const MAX_LEN: usize = 64;
+
+    /// Trims the name and collapses internal whitespace runs to one space,
+    /// so "food " and "food" end up as the same category.
     pub fn new(name: impl Into<String>) -> Result<Self, DomainError> {
-        let name = name.into();
-        if name.trim().is_empty() {
+        let name = name.into().split_whitespace().collect::<Vec<_>>().join(" ");
+        if name.is_empty() {
             return Err(DomainError::InvalidData(
                 "Category cannot be empty".to_string(),
             ));
         }
+        if name.chars().count() > Self::MAX_LEN {
+            return Err(DomainError::InvalidData(format!(
+                "Category cannot be longer than {} characters",
+                Self::MAX_LEN
+            )));
+        }
         Ok(Self(name))
     }
 
+    /// Wraps a name read back from storage without re-checking it. The
+    /// rules above apply to names being written; a row stored before they
+    /// existed (say, longer than `MAX_LEN`) must not make the ledger
+    /// unreadable.
+    pub fn from_stored(name: String) -> Self {
+        Self(name)
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
 }
 
+impl TryFrom<String> for Category {
+    type Error = DomainError;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Self::new(name)
+    }
+}
+
+impl From<Category> for String {
+    fn from(category: Category) -> Self {
+        category.0
+    }
+}
+
 impl AsRef<str> for Category {
     fn as_ref(&self) -> &str {
         &self.0
@@ -43,38 +107,354 @@ impl AsRef<str> for Category {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Entry {
     pub id: EntryId,
     pub kind: EntryKind,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_money"))]
     pub amount: Money<'static, iso::Currency>,
     pub category: Category,
     pub note: Option<String>,
     pub occurred_on: NaiveDate,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub account: Option<AccountId>,
+    /// Free-form labels, sorted by name.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tags: Vec<String>,
+    /// Matched against a bank statement (reconciled).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cleared: bool,
+    /// Starts at 1 and goes up with every update. `update_entry` only
+    /// succeeds if this still matches what is stored.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub version: u32,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NewEntry {
     pub kind: EntryKind,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_money"))]
     pub amount: Money<'static, iso::Currency>,
     pub category: Category,
     pub note: Option<String>,
     pub occurred_on: NaiveDate,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub account: Option<AccountId>,
+    /// Labels to attach; surrounding whitespace is trimmed, blanks and
+    /// duplicates are dropped when stored.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tags: Vec<String>,
 }
 
 impl NewEntry {
     pub fn validate(&self) -> Result<(), DomainError> {
+        self.validate_with(Local::now().date_naive())
+    }
+
+    /// Like [`validate`](Self::validate), but with an explicit "today" so
+    /// the future-date check is deterministic. Dates up to one day ahead are
+    /// allowed to tolerate time zone differences.
+    pub fn validate_with(&self, today: NaiveDate) -> Result<(), DomainError> {
         if self.amount.is_negative() || self.amount.is_zero() {
             return Err(DomainError::InvalidData(
                 "Amount must be positive".to_string(),
             ));
         }
+        if today
+            .checked_add_days(Days::new(1))
+            .is_some_and(|limit| self.occurred_on > limit)
+        {
+            return Err(DomainError::InvalidData(
+                "Date cannot be in the future".to_string(),
+            ));
+        }
         Ok(())
     }
 }
 
+/// Fluent construction of a [`NewEntry`]. Defaults to an expense dated
+/// today with no note or account; amount and category are required.
+#[derive(Debug, Clone)]
+pub struct NewEntryBuilder {
+    kind: EntryKind,
+    amount: Option<Money<'static, iso::Currency>>,
+    category: Option<Category>,
+    note: Option<String>,
+    occurred_on: NaiveDate,
+    account: Option<AccountId>,
+    tags: Vec<String>,
+}
+
+impl NewEntryBuilder {
+    pub fn new() -> Self {
+        Self {
+            kind: EntryKind::Expense,
+            amount: None,
+            category: None,
+            note: None,
+            occurred_on: Local::now().date_naive(),
+            account: None,
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn kind(mut self, kind: EntryKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn amount(mut self, amount: Money<'static, iso::Currency>) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    pub fn category(mut self, category: Category) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    pub fn note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+
+    pub fn occurred_on(mut self, occurred_on: NaiveDate) -> Self {
+        self.occurred_on = occurred_on;
+        self
+    }
+
+    pub fn account(mut self, account: AccountId) -> Self {
+        self.account = Some(account);
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Assembles the entry and runs [`NewEntry::validate`] on it.
+    pub fn build(self) -> Result<NewEntry, DomainError> {
+        let amount = self
+            .amount
+            .ok_or_else(|| DomainError::InvalidData("Amount is required".to_string()))?;
+        let category = self
+            .category
+            .ok_or_else(|| DomainError::InvalidData("Category is required".to_string()))?;
+        let entry = NewEntry {
+            kind: self.kind,
+            amount,
+            category,
+            note: self.note,
+            occurred_on: self.occurred_on,
+            account: self.account,
+            tags: self.tags,
+        };
+        entry.validate()?;
+        Ok(entry)
+    }
+}
+
+impl Default for NewEntryBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A rule that produces one entry per occurrence from `start_date` up to
+/// `end_date` (inclusive, if set).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurringEntry {
+    pub kind: EntryKind,
+    pub amount: Money<'static, iso::Currency>,
+    pub category: Category,
+    pub note: Option<String>,
+    pub frequency: Frequency,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+}
+
+impl RecurringEntry {
+    /// Date of the `n`-th occurrence (0-based), or `None` once past `end_date`.
+    ///
+    /// Each occurrence is computed from `start_date`, so a rule starting on
+    /// the 31st lands on the last day of shorter months without drifting.
+    pub fn occurrence(&self, n: u32) -> Option<NaiveDate> {
+        let date = match self.frequency {
+            Frequency::Weekly => self
+                .start_date
+                .checked_add_days(Days::new(7 * u64::from(n))),
+            Frequency::Monthly => self.start_date.checked_add_months(Months::new(n)),
+            Frequency::Yearly => self
+                .start_date
+                .checked_add_months(Months::new(n.checked_mul(12)?)),
+        }?;
+        match self.end_date {
+            Some(end) if date > end => None,
+            _ => Some(date),
+        }
+    }
+
+    pub fn to_new_entry(&self, occurred_on: NaiveDate) -> NewEntry {
+        NewEntry {
+            kind: self.kind,
+            amount: self.amount,
+            category: self.category.clone(),
+            note: self.note.clone(),
+            occurred_on,
+            account: None,
+            tags: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EntrySort {
+    #[default]
+    DateDesc,
+    DateAsc,
+    AmountDesc,
+    AmountAsc,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct EntryFilter {
     pub from: Option<NaiveDate>,
     pub to: Option<NaiveDate>,
     pub category: Option<Category>,
+    pub account: Option<AccountId>,
+    /// Only entries carrying this tag.
+    pub tag: Option<String>,
+    pub kind: Option<EntryKind>,
+    pub min_cents: Option<i64>,
+    pub max_cents: Option<i64>,
+    pub note_contains: Option<String>,
+    /// Also match deleted (archived) entries.
+    pub include_archived: bool,
+    /// Skip entries already marked cleared.
+    pub only_uncleared: bool,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+    pub sort: EntrySort,
+}
+
+/// Income and expense sums over a filtered set of entries, in minor units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub income_cents: i64,
+    pub expense_cents: i64,
+}
+
+impl Totals {
+    pub fn net_cents(&self) -> i64 {
+        self.income_cents - self.expense_cents
+    }
+}
+
+/// Income and expense sums for one calendar month (`1..=12`), in minor units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MonthlyTotals {
+    pub month: u32,
+    pub income_cents: i64,
+    pub expense_cents: i64,
+}
+
+/// Sum of matching entries in one category, in minor units.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryTotal {
+    pub category: Category,
+    pub total_cents: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry_on(occurred_on: NaiveDate) -> NewEntry {
+        NewEntry {
+            kind: EntryKind::Expense,
+            amount: Money::from_minor(500, iso::USD),
+            category: Category::new("food").unwrap(),
+            note: None,
+            occurred_on,
+            account: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn validate_rejects_far_future_dates() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 15).expect("date");
+        assert!(entry_on(today).validate_with(today).is_ok());
+        assert!(
+            entry_on(today.pred_opt().unwrap())
+                .validate_with(today)
+                .is_ok()
+        );
+        assert!(matches!(
+            entry_on(NaiveDate::from_ymd_opt(20224, 6, 15).expect("date")).validate_with(today),
+            Err(DomainError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn builder_fills_defaults() {
+        let entry = NewEntryBuilder::new()
+            .amount(Money::from_minor(1250, iso::USD))
+            .category(Category::new("food").unwrap())
+            .build()
+            .expect("entry built");
+        assert_eq!(entry.kind, EntryKind::Expense);
+        assert_eq!(entry.note, None);
+        assert_eq!(entry.occurred_on, Local::now().date_naive());
+    }
+
+    #[test]
+    fn builder_surfaces_validation_errors() {
+        let result = NewEntryBuilder::new()
+            .kind(EntryKind::Income)
+            .amount(Money::from_minor(0, iso::USD))
+            .category(Category::new("salary").unwrap())
+            .build();
+        assert!(matches!(result, Err(DomainError::InvalidData(_))));
+
+        let result = NewEntryBuilder::new()
+            .amount(Money::from_minor(100, iso::USD))
+            .build();
+        assert!(matches!(result, Err(DomainError::InvalidData(_))));
+    }
+
+    #[test]
+    fn category_names_are_normalized() {
+        assert_eq!(Category::new("  food ").unwrap().as_str(), "food");
+        assert_eq!(
+            Category::new("eating\t  out\n").unwrap().as_str(),
+            "eating out"
+        );
+        assert_eq!(
+            Category::new("eating out").unwrap(),
+            Category::new(" eating   out").unwrap()
+        );
+    }
+
+    #[test]
+    fn category_rejects_empty_and_overlong_names() {
+        assert!(matches!(
+            Category::new(" \t "),
+            Err(DomainError::InvalidData(_))
+        ));
+        assert!(Category::new("x".repeat(Category::MAX_LEN)).is_ok());
+        assert!(matches!(
+            Category::new("x".repeat(Category::MAX_LEN + 1)),
+            Err(DomainError::InvalidData(_))
+        ));
+    }
 }
@@ -1,14 +1,83 @@
+use crate::currency::DEFAULT_CURRENCY;
 use crate::error::DomainError;
-use crate::models::{Entry, EntryFilter, NewEntry};
+use crate::models::{
+    Account, Category, CategoryTotal, Entry, EntryFilter, EntryId, MonthlyTotals, NewEntry,
+    RecurringEntry, Totals,
+};
 use crate::user::User;
+use chrono::NaiveDate;
+use rusty_money::iso;
 
 pub trait EntryRepository: UserRepository {
     fn add(&mut self, entry: NewEntry) -> Result<Entry, DomainError>;
     fn list(&self, filter: EntryFilter) -> Result<Vec<Entry>, DomainError>;
+    /// Number of entries matching `filter`, ignoring its `limit` and `offset`
+    /// (the total to page through).
+    fn count(&self, filter: EntryFilter) -> Result<u64, DomainError>;
+    fn top_entries(&self, filter: EntryFilter, n: usize) -> Result<Vec<Entry>, DomainError>;
+    fn get_entry(&self, id: EntryId) -> Result<Entry, DomainError>;
+    /// Saves the edited fields and tags of `entry` and returns it with its new
+    /// version. Fails with `Conflict` if the entry changed since `entry.version`
+    /// was read, and with `NotFound` if it no longer exists (or is archived).
+    /// `cleared` is not saved here; see `set_cleared`.
+    fn update_entry(&mut self, entry: Entry) -> Result<Entry, DomainError>;
+    /// Marks an entry as reconciled (or not). Does not change its version;
+    /// `NotFound` if there is no such live entry.
+    fn set_cleared(&mut self, id: EntryId, cleared: bool) -> Result<(), DomainError>;
+    /// Archives an entry, hiding it from queries unless
+    /// `EntryFilter::include_archived` is set. Returns `NotFound` if there is
+    /// no such entry or it is already archived.
+    fn delete_entry(&mut self, id: EntryId) -> Result<(), DomainError>;
+    /// Brings back an archived entry; `NotFound` if it is not archived.
+    fn restore_entry(&mut self, id: EntryId) -> Result<(), DomainError>;
+    /// Income and expense sums of the entries matching `filter`. Like every
+    /// sum below, only entries in `default_currency` are counted.
+    fn totals(&self, filter: EntryFilter) -> Result<Totals, DomainError>;
+    /// Totals for each month of `year`, always twelve rows (empty months are zero).
+    fn monthly_summary(&self, year: i32) -> Result<Vec<MonthlyTotals>, DomainError>;
+    /// Expense sum for every day from `from` to `to` inclusive, in order, in
+    /// minor units; days without expenses are zero.
+    fn daily_expense_totals(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, i64)>, DomainError>;
+    /// Per-category sums of the entries matching `filter`, largest first.
+    /// Set `filter.kind` to avoid mixing income and expenses.
+    fn category_breakdown(&self, filter: EntryFilter) -> Result<Vec<CategoryTotal>, DomainError>;
+    /// Distinct categories already in use, sorted by name (for autocomplete).
+    fn categories(&self) -> Result<Vec<Category>, DomainError>;
+    /// Moves every entry (archived ones too) in any of `sources` to `target`,
+    /// which need not exist yet, and returns how many entries moved.
+    fn merge_categories(
+        &mut self,
+        sources: Vec<Category>,
+        target: Category,
+    ) -> Result<u64, DomainError>;
+    /// Creates a named account; duplicate names are a `Conflict`.
+    fn create_account(&mut self, name: &str) -> Result<Account, DomainError>;
+    fn list_accounts(&self) -> Result<Vec<Account>, DomainError>;
+    fn add_recurring(&mut self, rule: RecurringEntry) -> Result<(), DomainError>;
+    /// Records an entry for every occurrence up to `as_of` that has not been
+    /// recorded yet and returns the new entries.
+    fn expand_due(&mut self, as_of: NaiveDate) -> Result<Vec<Entry>, DomainError>;
+    /// Currency for new entries and for totals shown to the user.
+    fn default_currency(&self) -> &'static iso::Currency {
+        DEFAULT_CURRENCY
+    }
 }
 
 pub trait UserRepository {
     fn create_user(&mut self, username: &str, password: &str) -> Result<User, DomainError>;
     fn verify_user(&self, username: &str, password: &str) -> Result<Option<User>, DomainError>;
+    fn change_password(
+        &mut self,
+        username: &str,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<(), DomainError>;
+    /// Removes the account after checking its password. Entries are not owned
+    /// by users, so they are left untouched.
+    fn delete_user(&mut self, username: &str, password: &str) -> Result<(), DomainError>;
     fn list_users(&self) -> Result<Vec<String>, DomainError>;
 }
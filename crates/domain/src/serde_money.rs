@@ -0,0 +1,37 @@
+//! Serializes `Money` as `{ "amount_cents": i64, "currency": "USD" }` so the
+//! value survives without depending on `rusty_money`'s own representation.
+
+use crate::minor_units;
+use rusty_money::{Money, iso};
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize)]
+struct MoneyRepr {
+    amount_cents: i64,
+    currency: String,
+}
+
+pub fn serialize<S: Serializer>(
+    money: &Money<'static, iso::Currency>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let currency = money.currency();
+    let amount_cents = minor_units(money).ok_or_else(|| S::Error::custom("amount out of range"))?;
+
+    MoneyRepr {
+        amount_cents,
+        currency: currency.iso_alpha_code.to_string(),
+    }
+    .serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Money<'static, iso::Currency>, D::Error> {
+    let repr = MoneyRepr::deserialize(deserializer)?;
+    let currency = iso::find(&repr.currency)
+        .ok_or_else(|| D::Error::custom(format!("unknown currency: {}", repr.currency)))?;
+    Ok(Money::from_minor(repr.amount_cents, currency))
+}
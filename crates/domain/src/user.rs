@@ -1,5 +1,51 @@
+use crate::error::DomainError;
+
+pub const MIN_PASSWORD_LEN: usize = 8;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct User {
     pub id: i64,
     pub username: String,
 }
+
+/// The password policy shared by every place that sets a password: at least
+/// [`MIN_PASSWORD_LEN`] characters, one of which is not a letter.
+pub fn validate_password(password: &str) -> Result<(), DomainError> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(DomainError::InvalidData(format!(
+            "Password must be at least {MIN_PASSWORD_LEN} characters"
+        )));
+    }
+    if password.chars().all(char::is_alphabetic) {
+        return Err(DomainError::InvalidData(
+            "Password must contain a digit or symbol".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_short_passwords() {
+        assert!(matches!(
+            validate_password("a1"),
+            Err(DomainError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_letters_only() {
+        assert!(matches!(
+            validate_password("correcthorse"),
+            Err(DomainError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn accepts_long_mixed_passwords() {
+        assert!(validate_password("hunter2!").is_ok());
+    }
+}
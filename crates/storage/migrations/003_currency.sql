@@ -0,0 +1 @@
+ALTER TABLE entries ADD COLUMN currency_code TEXT NOT NULL DEFAULT 'USD';
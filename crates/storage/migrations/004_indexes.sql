@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS idx_entries_occurred_on ON entries(occurred_on);
+CREATE INDEX IF NOT EXISTS idx_entries_category ON entries(category);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS recurring_entries (
+    id INTEGER PRIMARY KEY,
+    kind TEXT NOT NULL,
+    amount_cents INTEGER NOT NULL,
+    currency_code TEXT NOT NULL,
+    category TEXT NOT NULL,
+    note TEXT,
+    frequency TEXT NOT NULL,
+    start_date TEXT NOT NULL,
+    end_date TEXT,
+    -- Number of occurrences already written to `entries`.
+    generated_count INTEGER NOT NULL DEFAULT 0
+);
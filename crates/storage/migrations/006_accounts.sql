@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS accounts (
+    id INTEGER PRIMARY KEY,
+    name TEXT UNIQUE NOT NULL
+);
+-- NULL means the entry is not assigned to any account.
+ALTER TABLE entries ADD COLUMN account_id INTEGER REFERENCES accounts(id);
+CREATE INDEX IF NOT EXISTS idx_entries_account ON entries(account_id);
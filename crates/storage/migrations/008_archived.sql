@@ -0,0 +1,2 @@
+-- Deleted entries are archived rather than removed, so they can be restored.
+ALTER TABLE entries ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
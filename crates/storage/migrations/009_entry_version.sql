@@ -0,0 +1,2 @@
+-- Bumped on every update so concurrent edits can be detected.
+ALTER TABLE entries ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,2 @@
+-- Set once an entry has been matched against a bank statement.
+ALTER TABLE entries ADD COLUMN cleared INTEGER NOT NULL DEFAULT 0;
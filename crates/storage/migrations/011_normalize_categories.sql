@@ -0,0 +1,17 @@
+-- Category names are trimmed with whitespace runs collapsed on write; bring
+-- rows stored before that in line so "food " and "food" group together.
+-- Tabs and line breaks become spaces first; then each halving pass shortens
+-- every run of spaces, so eight passes collapse runs of up to 256.
+UPDATE entries SET category = REPLACE(REPLACE(REPLACE(REPLACE(REPLACE(category,
+    char(9), ' '), char(10), ' '), char(11), ' '), char(12), ' '), char(13), ' ');
+UPDATE entries SET category = TRIM(REPLACE(REPLACE(REPLACE(REPLACE(
+    REPLACE(REPLACE(REPLACE(REPLACE(category,
+    '  ', ' '), '  ', ' '), '  ', ' '), '  ', ' '),
+    '  ', ' '), '  ', ' '), '  ', ' '), '  ', ' '));
+
+UPDATE recurring_entries SET category = REPLACE(REPLACE(REPLACE(REPLACE(REPLACE(category,
+    char(9), ' '), char(10), ' '), char(11), ' '), char(12), ' '), char(13), ' ');
+UPDATE recurring_entries SET category = TRIM(REPLACE(REPLACE(REPLACE(REPLACE(
+    REPLACE(REPLACE(REPLACE(REPLACE(category,
+    '  ', ' '), '  ', ' '), '  ', ' '), '  ', ' '),
+    '  ', ' '), '  ', ' '), '  ', ' '), '  ', ' '));
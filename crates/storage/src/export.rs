@@ -0,0 +1,288 @@
+use crate::mapper;
+use crate::repository::DATE_FORMAT;
+use domain::{Category, DomainError, Entry, EntryFilter, EntryKind, EntryRepository};
+use rusty_money::Money;
+use std::io::Write;
+
+const CSV_HEADER: &str = "id,kind,amount_cents,currency,category,note,occurred_on";
+
+/// Writes the entries matching `filter` as CSV, one row per entry.
+pub fn export_csv<W: Write>(
+    repo: &dyn EntryRepository,
+    filter: EntryFilter,
+    out: W,
+) -> Result<(), DomainError> {
+    let entries = repo.list(filter)?;
+    write_csv(&entries, out)
+}
+
+/// Writes every entry in `category` as CSV, in the same format as
+/// [`export_csv`].
+pub fn export_category_csv<W: Write>(
+    repo: &dyn EntryRepository,
+    category: &Category,
+    out: W,
+) -> Result<(), DomainError> {
+    let filter = EntryFilter {
+        category: Some(category.clone()),
+        ..EntryFilter::default()
+    };
+    export_csv(repo, filter, out)
+}
+
+/// Writes the entries matching `filter` as a pretty-printed JSON array.
+pub fn export_json<W: Write>(
+    repo: &dyn EntryRepository,
+    filter: EntryFilter,
+    mut out: W,
+) -> Result<(), DomainError> {
+    let entries = repo.list(filter)?;
+    serde_json::to_writer_pretty(&mut out, &entries)
+        .map_err(|err| DomainError::Storage(err.to_string()))?;
+    writeln!(out).map_err(io_error)?;
+    out.flush().map_err(io_error)
+}
+
+/// Writes the entries matching `filter` as a GitHub-flavored Markdown table,
+/// ending with a totals row (net amount, with income and expenses in the note
+/// column). Each currency gets its own totals row, the repository's default
+/// currency first; amounts in different currencies are never added up.
+pub fn export_markdown<W: Write>(
+    repo: &dyn EntryRepository,
+    filter: EntryFilter,
+    mut out: W,
+) -> Result<(), DomainError> {
+    let entries = repo.list(filter)?;
+    let currency = repo.default_currency();
+
+    writeln!(out, "| Date | Kind | Category | Amount | Note |").map_err(io_error)?;
+    writeln!(out, "| --- | --- | --- | ---: | --- |").map_err(io_error)?;
+    // (currency, income, expense) in minor units.
+    let mut totals = vec![(currency, 0, 0)];
+    for entry in &entries {
+        let row = match totals
+            .iter()
+            .position(|(c, _, _)| *c == entry.amount.currency())
+        {
+            Some(index) => &mut totals[index],
+            None => {
+                totals.push((entry.amount.currency(), 0, 0));
+                totals.last_mut().expect("just pushed")
+            }
+        };
+        match entry.kind {
+            EntryKind::Income => row.1 += mapper::from_money(&entry.amount)?,
+            EntryKind::Expense => row.2 += mapper::from_money(&entry.amount)?,
+            EntryKind::Transfer => {}
+        }
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} |",
+            entry.occurred_on.format(DATE_FORMAT),
+            mapper::kind_to_str(entry.kind),
+            markdown_cell(entry.category.as_str()),
+            entry.amount,
+            markdown_cell(entry.note.as_deref().unwrap_or_default()),
+        )
+        .map_err(io_error)?;
+    }
+
+    totals[1..].sort_by_key(|(c, _, _)| c.iso_alpha_code);
+    let labelled = totals.len() > 1;
+    for (currency, income, expense) in totals {
+        let money = |cents: i64| Money::from_minor(cents, currency);
+        let label = if labelled {
+            format!("**Total {}**", currency.iso_alpha_code)
+        } else {
+            "**Total**".to_string()
+        };
+        writeln!(
+            out,
+            "| {label} | | | **{}** | income {}, expenses {} |",
+            money(income - expense),
+            money(income),
+            money(expense),
+        )
+        .map_err(io_error)?;
+    }
+    out.flush().map_err(io_error)
+}
+
+fn write_csv<W: Write>(entries: &[Entry], mut out: W) -> Result<(), DomainError> {
+    writeln!(out, "{CSV_HEADER}").map_err(io_error)?;
+    for entry in entries {
+        let fields = [
+            entry.id.to_string(),
+            mapper::kind_to_str(entry.kind).to_string(),
+            mapper::from_money(&entry.amount)?.to_string(),
+            mapper::currency_code(&entry.amount).to_string(),
+            entry.category.as_str().to_string(),
+            entry.note.clone().unwrap_or_default(),
+            entry.occurred_on.format(DATE_FORMAT).to_string(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        writeln!(out, "{}", row.join(",")).map_err(io_error)?;
+    }
+    out.flush().map_err(io_error)
+}
+
+/// Quotes a field when it contains a delimiter, quote, or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escapes pipes so they do not end the cell, and keeps multi-line notes on
+/// one table row.
+fn markdown_cell(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+fn io_error(err: std::io::Error) -> DomainError {
+    DomainError::Storage(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SqliteRepository;
+    use chrono::NaiveDate;
+    use domain::{EntryKind, NewEntry};
+    use rusty_money::{Money, iso};
+
+    #[test]
+    fn export_csv_writes_header_and_quoted_rows() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        repo.add(NewEntry {
+            kind: EntryKind::Expense,
+            amount: Money::from_minor(1234, iso::USD),
+            category: Category::new("food").unwrap(),
+            note: Some("lunch, with \"Bob\"".to_string()),
+            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 20).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+        repo.add(NewEntry {
+            kind: EntryKind::Income,
+            amount: Money::from_minor(500000, iso::USD),
+            category: Category::new("salary").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 31).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+
+        let mut out = Vec::new();
+        export_csv(&repo, EntryFilter::default(), &mut out).expect("exported");
+
+        assert_eq!(
+            String::from_utf8(out).expect("utf-8"),
+            "id,kind,amount_cents,currency,category,note,occurred_on\n\
+             2,income,500000,USD,salary,,2024-01-31\n\
+             1,expense,1234,USD,food,\"lunch, with \"\"Bob\"\"\",2024-01-20\n"
+        );
+    }
+
+    #[test]
+    fn export_category_csv_keeps_only_that_category() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        for (category, cents, day) in [("travel", 4500, 3), ("food", 1200, 4), ("travel", 900, 5)] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: Money::from_minor(cents, iso::USD),
+                category: Category::new(category).unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 2, day).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let mut out = Vec::new();
+        export_category_csv(&repo, &Category::new("travel").unwrap(), &mut out).expect("exported");
+
+        assert_eq!(
+            String::from_utf8(out).expect("utf-8"),
+            "id,kind,amount_cents,currency,category,note,occurred_on\n\
+             3,expense,900,USD,travel,,2024-02-05\n\
+             1,expense,4500,USD,travel,,2024-02-03\n"
+        );
+    }
+
+    #[test]
+    fn export_markdown_writes_table_with_totals() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.add(NewEntry {
+            kind: EntryKind::Expense,
+            amount: Money::from_minor(1234, iso::USD),
+            category: Category::new("food").unwrap(),
+            note: Some("lunch | dinner\nwith Bob".to_string()),
+            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 20).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+        repo.add(NewEntry {
+            kind: EntryKind::Income,
+            amount: Money::from_minor(500000, iso::USD),
+            category: Category::new("salary").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 31).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+
+        let mut out = Vec::new();
+        export_markdown(&repo, EntryFilter::default(), &mut out).expect("exported");
+
+        assert_eq!(
+            String::from_utf8(out).expect("utf-8"),
+            "| Date | Kind | Category | Amount | Note |\n\
+             | --- | --- | --- | ---: | --- |\n\
+             | 2024-01-31 | income | salary | $5,000.00 |  |\n\
+             | 2024-01-20 | expense | food | $12.34 | lunch \\| dinner<br>with Bob |\n\
+             | **Total** | | | **$4,987.66** | income $5,000.00, expenses $12.34 |\n"
+        );
+    }
+
+    #[test]
+    fn export_markdown_totals_each_currency_separately() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        for (cents, currency) in [(1000, iso::USD), (1500, iso::JPY), (250, iso::USD)] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: Money::from_minor(cents, currency),
+                category: Category::new("travel").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 1, 20).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let mut out = Vec::new();
+        export_markdown(&repo, EntryFilter::default(), &mut out).expect("exported");
+        let text = String::from_utf8(out).expect("utf-8");
+        let totals: Vec<&str> = text.lines().filter(|l| l.contains("**Total")).collect();
+        assert_eq!(
+            totals,
+            [
+                "| **Total USD** | | | **-$12.50** | income $0.00, expenses $12.50 |",
+                "| **Total JPY** | | | **-¥1,500** | income ¥0, expenses ¥1,500 |",
+            ]
+        );
+    }
+}
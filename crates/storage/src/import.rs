@@ -0,0 +1,340 @@
+use crate::mapper;
+use crate::repository::DATE_FORMAT;
+use chrono::NaiveDate;
+use domain::{Category, DomainError, Entry, EntryRepository, NewEntry};
+use std::io::Read;
+
+/// Outcome of a CSV import: rows that failed are reported by line number.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub inserted: usize,
+    pub errors: Vec<(usize, String)>,
+}
+
+/// Imports entries from CSV in the layout written by `export_csv`.
+///
+/// The header row decides the column order; `id` is ignored and `currency`
+/// defaults to the repository's default currency when absent. Invalid rows
+/// are skipped and reported while the remaining rows are still inserted.
+pub fn import_csv<R: Read>(
+    repo: &mut dyn EntryRepository,
+    mut input: R,
+) -> Result<ImportReport, DomainError> {
+    let mut text = String::new();
+    input
+        .read_to_string(&mut text)
+        .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+    let mut records = parse_records(&text).into_iter();
+    let mut report = ImportReport::default();
+    let Some((_, header)) = records.next() else {
+        return Ok(report);
+    };
+    let header = header.map_err(DomainError::InvalidData)?;
+    let columns = Columns::from_header(&header)?;
+    let currency = repo.default_currency().iso_alpha_code;
+
+    for (line, record) in records {
+        let result = record
+            .map_err(DomainError::InvalidData)
+            .and_then(|fields| columns.new_entry(&fields, currency))
+            .and_then(|entry| repo.add(entry));
+        match result {
+            Ok(_) => report.inserted += 1,
+            Err(err) => report.errors.push((line, err.to_string())),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Re-creates entries from a JSON array written by `export_json`.
+///
+/// Ids are not preserved: every entry is inserted through `add` and gets a
+/// fresh id. All entries are validated before the first insert, so an
+/// invalid record leaves the repository untouched. Returns the number of
+/// inserted entries.
+pub fn import_json<R: Read>(
+    repo: &mut dyn EntryRepository,
+    input: R,
+) -> Result<usize, DomainError> {
+    let entries: Vec<Entry> =
+        serde_json::from_reader(input).map_err(|err| DomainError::InvalidData(err.to_string()))?;
+
+    let new_entries = entries
+        .into_iter()
+        .map(|entry| {
+            let new_entry = NewEntry {
+                kind: entry.kind,
+                amount: entry.amount,
+                category: entry.category,
+                note: entry.note,
+                occurred_on: entry.occurred_on,
+                account: None,
+                tags: entry.tags,
+            };
+            new_entry.validate().map(|()| new_entry)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let count = new_entries.len();
+    for new_entry in new_entries {
+        repo.add(new_entry)?;
+    }
+    Ok(count)
+}
+
+struct Columns {
+    kind: usize,
+    amount_cents: usize,
+    currency: Option<usize>,
+    category: usize,
+    note: Option<usize>,
+    occurred_on: usize,
+}
+
+impl Columns {
+    fn from_header(header: &[String]) -> Result<Self, DomainError> {
+        let find = |name: &str| header.iter().position(|column| column.trim() == name);
+        let require = |name: &str| {
+            find(name).ok_or_else(|| DomainError::InvalidData(format!("missing column: {name}")))
+        };
+
+        Ok(Self {
+            kind: require("kind")?,
+            amount_cents: require("amount_cents")?,
+            currency: find("currency"),
+            category: require("category")?,
+            note: find("note"),
+            occurred_on: require("occurred_on")?,
+        })
+    }
+
+    fn new_entry(
+        &self,
+        fields: &[String],
+        default_currency: &str,
+    ) -> Result<NewEntry, DomainError> {
+        let field = |index: usize| {
+            fields.get(index).map(String::as_str).ok_or_else(|| {
+                DomainError::InvalidData(format!(
+                    "expected at least {} columns, found {}",
+                    index + 1,
+                    fields.len()
+                ))
+            })
+        };
+
+        let kind = mapper::kind_from_str(field(self.kind)?.trim().to_string())?;
+        let amount_cents = field(self.amount_cents)?
+            .trim()
+            .parse::<i64>()
+            .map_err(|_| DomainError::InvalidData("amount_cents must be an integer".to_string()))?;
+        let currency = match self.currency {
+            Some(index) => field(index)?.trim(),
+            None => default_currency,
+        };
+        let amount = mapper::to_money(amount_cents, currency)?;
+        let category = Category::new(field(self.category)?)?;
+        let note = match self.note {
+            Some(index) => Some(field(index)?).filter(|note| !note.is_empty()),
+            None => None,
+        };
+        let occurred_on = NaiveDate::parse_from_str(field(self.occurred_on)?.trim(), DATE_FORMAT)
+            .map_err(|e| DomainError::InvalidData(e.to_string()))?;
+
+        let entry = NewEntry {
+            kind,
+            amount,
+            category,
+            note: note.map(str::to_string),
+            occurred_on,
+            account: None,
+            tags: Vec::new(),
+        };
+        entry.validate()?;
+        Ok(entry)
+    }
+}
+
+type Record = (usize, Result<Vec<String>, String>);
+
+/// Splits CSV text into records, keeping the line each record starts on.
+/// Quoted fields may contain commas, doubled quotes, and line breaks.
+fn parse_records(text: &str) -> Vec<Record> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut start_line = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(ch);
+                }
+                _ => field.push(ch),
+            }
+            continue;
+        }
+
+        match ch {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => fields.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                if !fields.is_empty() || !field.is_empty() {
+                    fields.push(std::mem::take(&mut field));
+                    records.push((start_line, Ok(std::mem::take(&mut fields))));
+                }
+                line += 1;
+                start_line = line;
+            }
+            _ => field.push(ch),
+        }
+    }
+
+    if in_quotes {
+        records.push((start_line, Err("unterminated quoted field".to_string())));
+    } else if !fields.is_empty() || !field.is_empty() {
+        fields.push(field);
+        records.push((start_line, Ok(fields)));
+    }
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SqliteRepository;
+    use crate::export::export_json;
+    use domain::{EntryFilter, EntryKind};
+    use rusty_money::{Money, iso};
+
+    #[test]
+    fn import_csv_reports_bad_rows_and_keeps_good_ones() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        let csv = "kind,amount_cents,category,note,occurred_on\n\
+                   expense,1250,food,\"lunch, downtown\",2024-01-20\n\
+                   expense,-300,food,refund?,2024-01-21\n\
+                   income,500000,salary,,2024-01-31\n";
+
+        let report = import_csv(&mut repo, csv.as_bytes()).expect("imported");
+
+        assert_eq!(report.inserted, 2);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].0, 3);
+
+        let entries = repo.list(EntryFilter::default()).expect("entries listed");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].note.as_deref(), Some("lunch, downtown"));
+        assert_eq!(entries[0].note, None);
+    }
+
+    #[test]
+    fn json_export_round_trips_through_import() {
+        let mut source = SqliteRepository::new_in_memory().expect("repo created");
+
+        source
+            .add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: Money::from_minor(1500, iso::JPY),
+                category: Category::new("sushi").unwrap(),
+                note: Some("omakase\nfor two".to_string()),
+                occurred_on: NaiveDate::from_ymd_opt(2024, 2, 3).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        source
+            .add(NewEntry {
+                kind: EntryKind::Income,
+                amount: Money::from_minor(250075, iso::USD),
+                category: Category::new("salary").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 2, 1).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+
+        let mut json = Vec::new();
+        export_json(&source, EntryFilter::default(), &mut json).expect("exported");
+        assert!(String::from_utf8_lossy(&json).contains("\"amount_cents\": 250075"));
+
+        let mut target = SqliteRepository::new_in_memory().expect("repo created");
+        let imported = import_json(&mut target, json.as_slice()).expect("imported");
+        assert_eq!(imported, 2);
+
+        let fields = |entries: Vec<Entry>| {
+            let mut fields: Vec<_> = entries
+                .into_iter()
+                .map(|e| (e.kind, e.amount, e.category, e.note, e.occurred_on))
+                .collect();
+            fields.sort_by_key(|f| f.4);
+            fields
+        };
+        assert_eq!(
+            fields(target.list(EntryFilter::default()).expect("listed")),
+            fields(source.list(EntryFilter::default()).expect("listed"))
+        );
+    }
+
+    #[test]
+    fn json_import_with_a_bad_record_inserts_nothing() {
+        let mut source = SqliteRepository::new_in_memory().expect("repo created");
+        for (category, day) in [("food", 1), ("rent", 2)] {
+            source
+                .add(NewEntry {
+                    kind: EntryKind::Expense,
+                    amount: Money::from_minor(1000, iso::USD),
+                    category: Category::new(category).unwrap(),
+                    note: None,
+                    occurred_on: NaiveDate::from_ymd_opt(2024, 3, day).expect("date"),
+                    account: None,
+                    tags: Vec::new(),
+                })
+                .expect("entry added");
+        }
+        let mut json = Vec::new();
+        export_json(&source, EntryFilter::default(), &mut json).expect("exported");
+        let mut records: serde_json::Value = serde_json::from_slice(&json).expect("valid json");
+        records[1]["amount"]["amount_cents"] = 0.into();
+
+        let mut target = SqliteRepository::new_in_memory().expect("repo created");
+        let result = import_json(&mut target, records.to_string().as_bytes());
+
+        assert!(matches!(result, Err(DomainError::InvalidData(_))));
+        assert!(
+            target
+                .list(EntryFilter::default())
+                .expect("listed")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn parse_records_handles_quotes_and_multiline_fields() {
+        let records = parse_records("a,\"b \"\"quoted\"\"\"\n\"multi\nline\",c\nd,e");
+
+        assert_eq!(
+            records,
+            vec![
+                (1, Ok(vec!["a".to_string(), "b \"quoted\"".to_string()])),
+                (2, Ok(vec!["multi\nline".to_string(), "c".to_string()])),
+                (4, Ok(vec!["d".to_string(), "e".to_string()])),
+            ]
+        );
+    }
+}
@@ -1,5 +1,8 @@
+mod export;
+mod import;
 mod mapper;
 mod repository;
 
+pub use export::{export_category_csv, export_csv, export_json, export_markdown};
+pub use import::{ImportReport, import_csv, import_json};
 pub use repository::SqliteRepository;
-// Re-export specific things if needed
@@ -1,33 +1,32 @@
-use domain::{DomainError, EntryKind};
+use domain::{DomainError, EntryKind, Frequency, minor_units};
 use rusty_money::{Money, iso};
 
-pub fn to_money(amount_cents: i64) -> Money<'static, iso::Currency> {
-    Money::from_minor(amount_cents, iso::USD)
+pub fn to_money(
+    amount_cents: i64,
+    currency_code: &str,
+) -> Result<Money<'static, iso::Currency>, DomainError> {
+    let currency = iso::find(currency_code)
+        .ok_or_else(|| DomainError::InvalidData(format!("unknown currency: {currency_code}")))?;
+    Ok(Money::from_minor(amount_cents, currency))
 }
 
-pub fn from_money(money: &Money<'static, iso::Currency>) -> i64 {
-    let s = money.amount().to_string();
-    if let Some(dot) = s.find('.') {
-        let (int, frac) = s.split_at(dot);
-        let frac = &frac[1..];
-        let mut cents = frac.to_string();
-        while cents.len() < 2 {
-            cents.push('0');
-        }
-        if cents.len() > 2 {
-            cents.truncate(2);
-        }
-        let total = format!("{}{}", int, cents);
-        total.parse::<i64>().unwrap_or(0)
-    } else {
-        s.parse::<i64>().unwrap_or(0) * 100
-    }
+pub fn currency_code(money: &Money<'static, iso::Currency>) -> &'static str {
+    money.currency().iso_alpha_code
+}
+
+/// Converts `money` into the minor units stored in `amount_cents`; see
+/// [`domain::minor_units`]. Amounts too large for the column are rejected.
+pub fn from_money(money: &Money<'static, iso::Currency>) -> Result<i64, DomainError> {
+    minor_units(money)
+        .ok_or_else(|| DomainError::InvalidData(format!("invalid amount: {}", money.amount())))
 }
 
 pub fn kind_to_str(kind: EntryKind) -> &'static str {
     match kind {
         EntryKind::Expense => "expense",
         EntryKind::Income => "income",
+        // `entries.kind` is free-form TEXT, so adding a kind needs no migration.
+        EntryKind::Transfer => "transfer",
     }
 }
 
@@ -35,8 +34,79 @@ pub fn kind_from_str(value: String) -> Result<EntryKind, DomainError> {
     match value.as_str() {
         "expense" => Ok(EntryKind::Expense),
         "income" => Ok(EntryKind::Income),
+        "transfer" => Ok(EntryKind::Transfer),
         _ => Err(DomainError::InvalidData(format!(
             "unknown entry kind: {value}"
         ))),
     }
 }
+
+pub fn frequency_to_str(frequency: Frequency) -> &'static str {
+    match frequency {
+        Frequency::Weekly => "weekly",
+        Frequency::Monthly => "monthly",
+        Frequency::Yearly => "yearly",
+    }
+}
+
+pub fn frequency_from_str(value: String) -> Result<Frequency, DomainError> {
+    match value.as_str() {
+        "weekly" => Ok(Frequency::Weekly),
+        "monthly" => Ok(Frequency::Monthly),
+        "yearly" => Ok(Frequency::Yearly),
+        _ => Err(DomainError::InvalidData(format!(
+            "unknown frequency: {value}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd(amount: &str) -> Money<'static, iso::Currency> {
+        Money::from_str(amount, iso::USD).expect("valid amount")
+    }
+
+    #[test]
+    fn from_money_rounds_half_up() {
+        assert_eq!(from_money(&usd("1.239")).unwrap(), 124);
+        assert_eq!(from_money(&usd("1.005")).unwrap(), 101);
+        assert_eq!(from_money(&usd("1.234")).unwrap(), 123);
+    }
+
+    #[test]
+    fn from_money_handles_whole_numbers() {
+        assert_eq!(from_money(&usd("12")).unwrap(), 1200);
+        assert_eq!(from_money(&Money::from_major(7, iso::USD)).unwrap(), 700);
+    }
+
+    #[test]
+    fn from_money_round_trips_minor_units() {
+        assert_eq!(from_money(&to_money(1234, "USD").unwrap()).unwrap(), 1234);
+        assert_eq!(from_money(&to_money(-505, "USD").unwrap()).unwrap(), -505);
+        assert_eq!(from_money(&to_money(1500, "JPY").unwrap()).unwrap(), 1500);
+    }
+
+    #[test]
+    fn from_money_uses_currency_exponent() {
+        let yen = Money::from_str("1500.6", iso::JPY).expect("valid amount");
+        assert_eq!(from_money(&yen).unwrap(), 1501);
+    }
+
+    #[test]
+    fn kinds_round_trip() {
+        for kind in [EntryKind::Expense, EntryKind::Income, EntryKind::Transfer] {
+            assert_eq!(kind_from_str(kind_to_str(kind).to_string()).unwrap(), kind);
+        }
+        assert!(kind_from_str("refund".to_string()).is_err());
+    }
+
+    #[test]
+    fn to_money_rejects_unknown_currency() {
+        assert!(matches!(
+            to_money(100, "XXX?"),
+            Err(DomainError::InvalidData(_))
+        ));
+    }
+}
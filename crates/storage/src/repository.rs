@@ -1,11 +1,17 @@
 use crate::mapper;
 use chrono::NaiveDate;
 use domain::{
-    Category, DomainError, Entry, EntryFilter, EntryId, EntryRepository, NewEntry, User,
-    UserRepository,
+    Account, AccountId, Category, CategoryTotal, DEFAULT_CURRENCY, DomainError, Entry, EntryFilter,
+    EntryId, EntryKind, EntryRepository, EntrySort, MonthlyTotals, NewEntry, RecurringEntry,
+    Totals, User, UserRepository, validate_password,
 };
-use rusqlite::{Connection, OptionalExtension, params};
+use rusqlite::types::Value;
+use rusqlite::{Connection, DatabaseName, ErrorCode, OptionalExtension, params};
+use rusty_money::iso;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
 
 use argon2::{
     Argon2,
@@ -16,23 +22,173 @@ use rand::rngs::OsRng;
 const MIGRATIONS: &[(&str, &str)] = &[
     ("001_init.sql", include_str!("../migrations/001_init.sql")),
     ("002_users.sql", include_str!("../migrations/002_users.sql")),
+    (
+        "003_currency.sql",
+        include_str!("../migrations/003_currency.sql"),
+    ),
+    (
+        "004_indexes.sql",
+        include_str!("../migrations/004_indexes.sql"),
+    ),
+    (
+        "005_recurring.sql",
+        include_str!("../migrations/005_recurring.sql"),
+    ),
+    (
+        "006_accounts.sql",
+        include_str!("../migrations/006_accounts.sql"),
+    ),
+    ("007_tags.sql", include_str!("../migrations/007_tags.sql")),
+    (
+        "008_archived.sql",
+        include_str!("../migrations/008_archived.sql"),
+    ),
+    (
+        "009_entry_version.sql",
+        include_str!("../migrations/009_entry_version.sql"),
+    ),
+    (
+        "010_cleared.sql",
+        include_str!("../migrations/010_cleared.sql"),
+    ),
+    (
+        "011_normalize_categories.sql",
+        include_str!("../migrations/011_normalize_categories.sql"),
+    ),
 ];
-const DATE_FORMAT: &str = "%Y-%m-%d";
+const BUSY_TIMEOUT: Duration = Duration::from_millis(5000);
+pub(crate) const DATE_FORMAT: &str = "%Y-%m-%d";
+/// Tag names are folded into one column, separated by `TAG_SEPARATOR`.
+const ENTRY_COLUMNS: &str = "id, kind, amount_cents, currency_code, category, note, occurred_on, account_id, cleared, version, \
+     (SELECT group_concat(t.name, char(31)) FROM entry_tags et \
+      JOIN tags t ON t.id = et.tag_id WHERE et.entry_id = entries.id) AS tags";
+const TAG_SEPARATOR: char = '\u{1f}';
 
 pub struct SqliteRepository {
     conn: Connection,
+    /// Distinct categories, loaded lazily and dropped whenever this
+    /// connection changes the set. Stored with `PRAGMA data_version`, which
+    /// moves when another connection commits, so their writes count too.
+    categories_cache: RefCell<Option<(i64, Vec<Category>)>>,
+    default_currency: &'static iso::Currency,
 }
 
 impl SqliteRepository {
     pub fn new(path: impl AsRef<Path>) -> Result<Self, DomainError> {
         let conn =
             Connection::open(path.as_ref()).map_err(|err| DomainError::Storage(err.to_string()))?;
-        let mut repo = Self { conn };
+        Self::from_file_connection(conn)
+    }
+
+    /// Opens (or creates) a SQLCipher database encrypted with `key`. A wrong
+    /// key is reported here rather than on the first query.
+    #[cfg(feature = "encryption")]
+    pub fn new_encrypted(path: impl AsRef<Path>, key: &str) -> Result<Self, DomainError> {
+        let conn =
+            Connection::open(path.as_ref()).map_err(|err| DomainError::Storage(err.to_string()))?;
+        conn.pragma_update(None, "key", key)
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        // SQLCipher only decrypts on first access, so touch the schema now.
+        conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .map_err(|_| {
+            DomainError::InvalidData(
+                "wrong encryption key, or the file is not an encrypted database".to_string(),
+            )
+        })?;
+        Self::from_file_connection(conn)
+    }
+
+    fn from_file_connection(conn: Connection) -> Result<Self, DomainError> {
+        // WAL lets readers and a writer work side by side, and the busy timeout
+        // makes a second process wait for a lock instead of failing outright.
+        // This is also the first read of the file, so garbage fails here.
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+            .map_err(|err| match err.sqlite_error_code() {
+                Some(ErrorCode::NotADatabase) => DomainError::InvalidData(
+                    "not a tui-money database (the file may be corrupt)".to_string(),
+                ),
+                _ => DomainError::Storage(err.to_string()),
+            })?;
+        conn.busy_timeout(BUSY_TIMEOUT)
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        let repo = Self::from_connection(conn)?;
+        repo.check_integrity()?;
+        Ok(repo)
+    }
+
+    /// Runs SQLite's `integrity_check`, so a damaged file is reported on open
+    /// rather than by some later query.
+    fn check_integrity(&self) -> Result<(), DomainError> {
+        let mut stmt = self
+            .conn
+            .prepare("PRAGMA integrity_check")
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        let problems = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|err| DomainError::Storage(err.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        match problems.as_slice() {
+            [ok] if ok == "ok" => Ok(()),
+            _ => Err(DomainError::InvalidData(format!(
+                "database is damaged: {}",
+                problems.join("; ")
+            ))),
+        }
+    }
+
+    /// Opens a private in-memory database, mainly for tests; nothing touches disk.
+    pub fn new_in_memory() -> Result<Self, DomainError> {
+        let conn =
+            Connection::open_in_memory().map_err(|err| DomainError::Storage(err.to_string()))?;
+        Self::from_connection(conn)
+    }
+
+    /// Uses `currency` (instead of USD) for new entries and displayed totals.
+    pub fn with_default_currency(mut self, currency: &'static iso::Currency) -> Self {
+        self.default_currency = currency;
+        self
+    }
+
+    /// Copies the open database to `dest` with SQLite's online backup API,
+    /// creating or overwriting the file. Safe to call while the database is
+    /// in use.
+    pub fn backup_to(&self, dest: impl AsRef<Path>) -> Result<(), DomainError> {
+        self.conn
+            .backup(DatabaseName::Main, dest.as_ref(), None)
+            .map_err(|err| DomainError::Storage(err.to_string()))
+    }
+
+    /// Migrations recorded in this database, oldest first. `applied_at` only
+    /// has second resolution, so ties fall back to the version name.
+    pub fn schema_versions(&self) -> Result<Vec<String>, DomainError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT version FROM schema_migrations ORDER BY applied_at, version")
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        stmt.query_map([], |row| row.get::<_, String>(0))
+            .map_err(|err| DomainError::Storage(err.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| DomainError::Storage(err.to_string()))
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self, DomainError> {
+        let mut repo = Self {
+            conn,
+            categories_cache: RefCell::new(None),
+            default_currency: DEFAULT_CURRENCY,
+        };
         repo.apply_migrations()?;
         Ok(repo)
     }
 
     fn apply_migrations(&mut self) -> Result<(), DomainError> {
+        self.run_migrations(MIGRATIONS)
+    }
+
+    fn run_migrations(&mut self, migrations: &[(&str, &str)]) -> Result<(), DomainError> {
         self.conn
             .execute(
                 "CREATE TABLE IF NOT EXISTS schema_migrations (
@@ -54,12 +210,25 @@ impl SqliteRepository {
                 .map_err(|err| DomainError::Storage(err.to_string()))?
         };
 
+        // A version we don't know was written by a newer build; its schema
+        // may not match what this one reads and writes.
+        if let Some(unknown) = applied
+            .iter()
+            .find(|version| !migrations.iter().any(|(known, _)| known == version))
+        {
+            log::error!("database has unknown migration {unknown}");
+            return Err(DomainError::InvalidData(format!(
+                "database is newer than this app (unknown migration {unknown}); \
+                 please upgrade tui-money"
+            )));
+        }
+
         let mut applied_set = std::collections::HashSet::new();
         for version in applied {
             applied_set.insert(version);
         }
 
-        for (version, sql) in MIGRATIONS {
+        for (version, sql) in migrations {
             if applied_set.contains(*version) {
                 continue;
             }
@@ -67,8 +236,10 @@ impl SqliteRepository {
                 .conn
                 .transaction()
                 .map_err(|err| DomainError::Storage(err.to_string()))?;
-            tx.execute_batch(sql)
-                .map_err(|err| DomainError::Storage(err.to_string()))?;
+            tx.execute_batch(sql).map_err(|err| {
+                log::error!("migration {version} failed: {err}");
+                DomainError::Storage(err.to_string())
+            })?;
             tx.execute(
                 "INSERT INTO schema_migrations (version) VALUES (?1)",
                 [*version],
@@ -76,76 +247,55 @@ impl SqliteRepository {
             .map_err(|err| DomainError::Storage(err.to_string()))?;
             tx.commit()
                 .map_err(|err| DomainError::Storage(err.to_string()))?;
+            log::info!("applied migration {version}");
         }
 
         Ok(())
     }
 }
 
-impl EntryRepository for SqliteRepository {
-    fn add(&mut self, entry: NewEntry) -> Result<Entry, DomainError> {
-        let kind = mapper::kind_to_str(entry.kind);
-        let occurred_on = entry.occurred_on.format(DATE_FORMAT).to_string();
-        let amount_cents = mapper::from_money(&entry.amount);
-        let category = entry.category.as_str();
+impl SqliteRepository {
+    /// `build_where` plus `currency_code = <default>`, since sums across
+    /// currencies mean nothing; callers show sums in the default currency.
+    fn sum_where(&self, filter: &EntryFilter) -> (String, Vec<Value>) {
+        let (mut conditions, mut params) = filter_conditions(filter);
+        conditions.push("currency_code = ?".to_string());
+        params.push(Value::Text(
+            self.default_currency.iso_alpha_code.to_string(),
+        ));
+        (format!(" WHERE {}", conditions.join(" AND ")), params)
+    }
 
-        self.conn
+    fn invalidate_categories(&self) {
+        self.categories_cache.borrow_mut().take();
+    }
+
+    /// Flips the `archived` flag; `NotFound` unless the entry exists and is
+    /// currently in the other state.
+    fn set_archived(&mut self, id: EntryId, archived: bool) -> Result<(), DomainError> {
+        let changed = self
+            .conn
             .execute(
-                "INSERT INTO entries (kind, amount_cents, category, note, occurred_on)
-                 VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![kind, amount_cents, category, entry.note, occurred_on],
+                "UPDATE entries SET archived = ?1 WHERE id = ?2 AND archived = ?3",
+                params![archived, id.0, !archived],
             )
             .map_err(|err| DomainError::Storage(err.to_string()))?;
-
-        let id = self.conn.last_insert_rowid();
-        Ok(Entry {
-            id: EntryId(id),
-            kind: entry.kind,
-            amount: entry.amount,
-            category: entry.category,
-            note: entry.note,
-            occurred_on: entry.occurred_on,
-        })
-    }
-
-    fn list(&self, filter: EntryFilter) -> Result<Vec<Entry>, DomainError> {
-        let mut conditions = Vec::new();
-        let mut params = Vec::new();
-
-        if let Some(from) = filter.from {
-            conditions.push("occurred_on >= ?".to_string());
-            params.push(from.format(DATE_FORMAT).to_string());
-        }
-        if let Some(to) = filter.to {
-            conditions.push("occurred_on <= ?".to_string());
-            params.push(to.format(DATE_FORMAT).to_string());
-        }
-        if let Some(category) = filter.category {
-            conditions.push("category = ?".to_string());
-            params.push(category.as_str().to_string());
+        if changed == 0 {
+            return Err(DomainError::NotFound);
         }
 
-        let mut query =
-            "SELECT id, kind, amount_cents, category, note, occurred_on FROM entries".to_string();
-        if !conditions.is_empty() {
-            query.push_str(" WHERE ");
-            query.push_str(&conditions.join(" AND "));
-        }
-        query.push_str(" ORDER BY occurred_on DESC, id DESC");
+        self.invalidate_categories();
+        Ok(())
+    }
 
+    fn query_entries(&self, query: &str, params: &[Value]) -> Result<Vec<Entry>, DomainError> {
         let mut stmt = self
             .conn
-            .prepare(&query)
+            .prepare(query)
             .map_err(|err| DomainError::Storage(err.to_string()))?;
 
-        // We need to build params dynamically, but rusqlite expects a trait.
-        // We can use split logic or ensure params are strict Strings.
-        // params vector is Vec<String>.
-        let params_refs: Vec<&dyn rusqlite::ToSql> =
-            params.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
-
         let mut rows = stmt
-            .query(params_refs.as_slice())
+            .query(rusqlite::params_from_iter(params))
             .map_err(|err| DomainError::Storage(err.to_string()))?;
 
         let mut entries = Vec::new();
@@ -153,213 +303,2435 @@ impl EntryRepository for SqliteRepository {
             .next()
             .map_err(|err| DomainError::Storage(err.to_string()))?
         {
-            let id: i64 = row
-                .get("id")
-                .map_err(|err| DomainError::Storage(err.to_string()))?;
-            let kind: String = row
-                .get("kind")
-                .map_err(|err| DomainError::Storage(err.to_string()))?;
-            let amount_cents: i64 = row
-                .get("amount_cents")
-                .map_err(|err| DomainError::Storage(err.to_string()))?;
-            let category_str: String = row
-                .get("category")
-                .map_err(|err| DomainError::Storage(err.to_string()))?;
-            let note: Option<String> = row
-                .get("note")
-                .map_err(|err| DomainError::Storage(err.to_string()))?;
-            let occurred_on_str: String = row
-                .get("occurred_on")
-                .map_err(|err| DomainError::Storage(err.to_string()))?;
-
-            // Conversions
-            let kind = mapper::kind_from_str(kind)?;
-            let amount = mapper::to_money(amount_cents);
-            let category = Category::new(category_str)?;
-            let occurred_on = NaiveDate::parse_from_str(&occurred_on_str, DATE_FORMAT)
-                .map_err(|e: chrono::ParseError| DomainError::InvalidData(e.to_string()))?;
-
-            entries.push(Entry {
-                id: EntryId(id),
-                kind,
-                amount,
-                category,
-                note,
-                occurred_on,
-            });
+            let entry_row =
+                EntryRow::from_row(row).map_err(|err| DomainError::Storage(err.to_string()))?;
+            entries.push(entry_row.into_entry()?);
         }
 
         Ok(entries)
     }
 }
 
-impl UserRepository for SqliteRepository {
-    fn create_user(&mut self, username: &str, password: &str) -> Result<User, DomainError> {
-        let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
-        let password_hash = argon2
-            .hash_password(password.as_bytes(), &salt)
-            .map_err(|e| DomainError::Storage(format!("Hashing failed: {}", e)))?
-            .to_string();
+/// The ` WHERE ...` clause (empty when nothing is filtered) and positional
+/// params for `filter`. Every query over `entries` goes through here, so the
+/// filter means the same thing everywhere.
+fn build_where(filter: &EntryFilter) -> (String, Vec<Value>) {
+    let (conditions, params) = filter_conditions(filter);
+    if conditions.is_empty() {
+        return (String::new(), params);
+    }
+    (format!(" WHERE {}", conditions.join(" AND ")), params)
+}
 
-        self.conn
-            .execute(
-                "INSERT INTO users (username, password_hash) VALUES (?1, ?2)",
-                params![username, password_hash],
-            )
-            .map_err(|err| DomainError::Storage(err.to_string()))?;
+/// Translates an `EntryFilter` into SQL conditions and their positional
+/// params, typed so that integers bind as integers.
+fn filter_conditions(filter: &EntryFilter) -> (Vec<String>, Vec<Value>) {
+    let mut conditions = Vec::new();
+    let mut params = Vec::new();
 
-        let id = self.conn.last_insert_rowid();
+    if !filter.include_archived {
+        conditions.push("archived = 0".to_string());
+    }
+    if let Some(from) = filter.from {
+        conditions.push("occurred_on >= ?".to_string());
+        params.push(Value::Text(from.format(DATE_FORMAT).to_string()));
+    }
+    if let Some(to) = filter.to {
+        conditions.push("occurred_on <= ?".to_string());
+        params.push(Value::Text(to.format(DATE_FORMAT).to_string()));
+    }
+    if let Some(category) = &filter.category {
+        conditions.push("category = ?".to_string());
+        params.push(Value::Text(category.as_str().to_string()));
+    }
+    if let Some(account) = filter.account {
+        conditions.push("account_id = ?".to_string());
+        params.push(Value::Integer(account.0));
+    }
+    if let Some(tag) = &filter.tag {
+        conditions.push(
+            "id IN (SELECT et.entry_id FROM entry_tags et \
+             JOIN tags t ON t.id = et.tag_id WHERE t.name = ?)"
+                .to_string(),
+        );
+        params.push(Value::Text(tag.trim().to_string()));
+    }
+    if let Some(kind) = filter.kind {
+        conditions.push("kind = ?".to_string());
+        params.push(Value::Text(mapper::kind_to_str(kind).to_string()));
+    }
+    if filter.only_uncleared {
+        conditions.push("cleared = 0".to_string());
+    }
+    if let Some(min_cents) = filter.min_cents {
+        conditions.push("amount_cents >= ?".to_string());
+        params.push(Value::Integer(min_cents));
+    }
+    if let Some(max_cents) = filter.max_cents {
+        conditions.push("amount_cents <= ?".to_string());
+        params.push(Value::Integer(max_cents));
+    }
+    if let Some(needle) = filter.note_contains.as_deref().filter(|n| !n.is_empty()) {
+        conditions.push("note LIKE '%' || ? || '%' ESCAPE '\\'".to_string());
+        params.push(Value::Text(escape_like(needle)));
+    }
 
-        Ok(User {
-            id,
-            username: username.to_string(),
+    (conditions, params)
+}
+
+fn order_by(sort: EntrySort) -> &'static str {
+    match sort {
+        EntrySort::DateDesc => "occurred_on DESC, id DESC",
+        EntrySort::DateAsc => "occurred_on ASC, id ASC",
+        EntrySort::AmountDesc => "amount_cents DESC, occurred_on DESC, id DESC",
+        EntrySort::AmountAsc => "amount_cents ASC, occurred_on DESC, id DESC",
+    }
+}
+
+/// A `recurring_entries` row plus its bookkeeping columns.
+struct RecurringRow {
+    id: i64,
+    kind: String,
+    amount_cents: i64,
+    currency_code: String,
+    category: String,
+    note: Option<String>,
+    frequency: String,
+    start_date: String,
+    end_date: Option<String>,
+    generated_count: u32,
+}
+
+impl RecurringRow {
+    fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            kind: row.get("kind")?,
+            amount_cents: row.get("amount_cents")?,
+            currency_code: row.get("currency_code")?,
+            category: row.get("category")?,
+            note: row.get("note")?,
+            frequency: row.get("frequency")?,
+            start_date: row.get("start_date")?,
+            end_date: row.get("end_date")?,
+            generated_count: row.get("generated_count")?,
         })
     }
 
-    fn verify_user(&self, username: &str, password: &str) -> Result<Option<User>, DomainError> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT id, username, password_hash FROM users WHERE username = ?1")
-            .map_err(|err| DomainError::Storage(err.to_string()))?;
+    fn into_rule(self) -> Result<RecurringEntry, DomainError> {
+        let parse_date = |value: &str| {
+            NaiveDate::parse_from_str(value, DATE_FORMAT)
+                .map_err(|e: chrono::ParseError| DomainError::InvalidData(e.to_string()))
+        };
 
-        let user_row = stmt
-            .query_row([username], |row| {
-                let id: i64 = row.get(0)?;
-                let username: String = row.get(1)?;
-                let password_hash: String = row.get(2)?;
-                Ok((id, username, password_hash))
-            })
-            .optional()
+        Ok(RecurringEntry {
+            kind: mapper::kind_from_str(self.kind)?,
+            amount: mapper::to_money(self.amount_cents, &self.currency_code)?,
+            category: Category::from_stored(self.category),
+            note: self.note,
+            frequency: mapper::frequency_from_str(self.frequency)?,
+            start_date: parse_date(&self.start_date)?,
+            end_date: self.end_date.as_deref().map(parse_date).transpose()?,
+        })
+    }
+}
+
+/// Inserts `entry` on `conn` (which may be a transaction) and returns it with its id.
+fn insert_entry(conn: &Connection, entry: NewEntry) -> Result<Entry, DomainError> {
+    let kind = mapper::kind_to_str(entry.kind);
+    let occurred_on = entry.occurred_on.format(DATE_FORMAT).to_string();
+    let amount_cents = mapper::from_money(&entry.amount)?;
+    let currency_code = mapper::currency_code(&entry.amount);
+    let category = entry.category.as_str();
+
+    conn.execute(
+        "INSERT INTO entries
+            (kind, amount_cents, currency_code, category, note, occurred_on, account_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            kind,
+            amount_cents,
+            currency_code,
+            category,
+            entry.note,
+            occurred_on,
+            entry.account.map(|account| account.0)
+        ],
+    )
+    .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+    let id = conn.last_insert_rowid();
+    let tags = normalize_tags(entry.tags);
+    insert_tags(conn, id, &tags)?;
+
+    Ok(Entry {
+        id: EntryId(id),
+        kind: entry.kind,
+        amount: entry.amount,
+        category: entry.category,
+        note: entry.note,
+        occurred_on: entry.occurred_on,
+        account: entry.account,
+        tags,
+        cleared: false,
+        version: 1,
+    })
+}
+
+fn insert_tags(conn: &Connection, entry_id: i64, tags: &[String]) -> Result<(), DomainError> {
+    for tag in tags {
+        conn.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", [tag])
             .map_err(|err| DomainError::Storage(err.to_string()))?;
+        conn.execute(
+            "INSERT INTO entry_tags (entry_id, tag_id) SELECT ?1, id FROM tags WHERE name = ?2",
+            params![entry_id, tag],
+        )
+        .map_err(|err| DomainError::Storage(err.to_string()))?;
+    }
+    Ok(())
+}
 
-        if let Some((id, username, password_hash)) = user_row {
-            let parsed_hash = PasswordHash::new(&password_hash)
-                .map_err(|e| DomainError::Storage(format!("Invalid hash: {}", e)))?;
+/// Trims tags and drops blanks and duplicates; the result is sorted so it
+/// matches what is read back.
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .into_iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
 
-            if Argon2::default()
-                .verify_password(password.as_bytes(), &parsed_hash)
-                .is_ok()
-            {
-                return Ok(Some(User { id, username }));
-            }
+/// Escapes LIKE wildcards so user input is matched literally.
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if matches!(ch, '\\' | '%' | '_') {
+            escaped.push('\\');
         }
+        escaped.push(ch);
+    }
+    escaped
+}
 
-        Ok(None)
+/// Raw column values of an `entries` row, decoded into an `Entry` separately so
+/// that every query shares the same conversion rules.
+struct EntryRow {
+    id: i64,
+    kind: String,
+    amount_cents: i64,
+    currency_code: String,
+    category: String,
+    note: Option<String>,
+    occurred_on: String,
+    account_id: Option<i64>,
+    cleared: bool,
+    version: u32,
+    tags: Option<String>,
+}
+
+impl EntryRow {
+    fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            kind: row.get("kind")?,
+            amount_cents: row.get("amount_cents")?,
+            currency_code: row.get("currency_code")?,
+            category: row.get("category")?,
+            note: row.get("note")?,
+            occurred_on: row.get("occurred_on")?,
+            account_id: row.get("account_id")?,
+            cleared: row.get("cleared")?,
+            version: row.get("version")?,
+            tags: row.get("tags")?,
+        })
     }
 
-    fn list_users(&self) -> Result<Vec<String>, DomainError> {
-        let mut stmt = self
+    fn into_entry(self) -> Result<Entry, DomainError> {
+        let kind = mapper::kind_from_str(self.kind)?;
+        let amount = mapper::to_money(self.amount_cents, &self.currency_code)?;
+        let category = Category::from_stored(self.category);
+        let occurred_on = NaiveDate::parse_from_str(&self.occurred_on, DATE_FORMAT)
+            .map_err(|e: chrono::ParseError| DomainError::InvalidData(e.to_string()))?;
+
+        Ok(Entry {
+            id: EntryId(self.id),
+            kind,
+            amount,
+            category,
+            note: self.note,
+            occurred_on,
+            account: self.account_id.map(AccountId),
+            tags: self.tags.map_or_else(Vec::new, |tags| {
+                normalize_tags(tags.split(TAG_SEPARATOR).map(str::to_string).collect())
+            }),
+            cleared: self.cleared,
+            version: self.version,
+        })
+    }
+}
+
+impl EntryRepository for SqliteRepository {
+    fn add(&mut self, entry: NewEntry) -> Result<Entry, DomainError> {
+        let tx = self
             .conn
-            .prepare("SELECT username FROM users ORDER BY username")
+            .transaction()
             .map_err(|err| DomainError::Storage(err.to_string()))?;
-
-        let users = stmt
-            .query_map([], |row| row.get(0))
-            .map_err(|err| DomainError::Storage(err.to_string()))?
-            .collect::<Result<Vec<String>, _>>()
+        let entry = insert_entry(&tx, entry)?;
+        tx.commit()
             .map_err(|err| DomainError::Storage(err.to_string()))?;
-
-        Ok(users)
+        self.invalidate_categories();
+        log::info!("added entry {}", entry.id.0);
+        Ok(entry)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::NaiveDate;
-    use domain::{Category, EntryFilter, EntryKind, NewEntry};
-    use rusty_money::{Money, iso};
-    use std::fs;
-    use std::time::{SystemTime, UNIX_EPOCH};
+    fn list(&self, filter: EntryFilter) -> Result<Vec<Entry>, DomainError> {
+        let (where_clause, mut params) = build_where(&filter);
 
-    fn temp_db_path(name: &str) -> std::path::PathBuf {
-        let suffix = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("time moves forward")
-            .as_nanos();
-        std::env::temp_dir().join(format!("tui-money-{name}-{suffix}.db"))
-    }
+        let mut query = format!("SELECT {ENTRY_COLUMNS} FROM entries{where_clause}");
+        query.push_str(" ORDER BY ");
+        query.push_str(order_by(filter.sort));
+        if filter.limit.is_some() || filter.offset.is_some() {
+            // SQLite only accepts OFFSET after LIMIT; -1 means "no limit".
+            query.push_str(" LIMIT ? OFFSET ?");
+            params.push(Value::Integer(filter.limit.map_or(-1, i64::from)));
+            params.push(Value::Integer(filter.offset.map_or(0, i64::from)));
+        }
 
-    fn usd(amount: i64) -> Money<'static, iso::Currency> {
-        Money::from_minor(amount, iso::USD)
+        self.query_entries(&query, &params)
     }
 
-    #[test]
-    fn add_and_list_entries() {
-        let path = temp_db_path("add-list");
-        let mut repo = SqliteRepository::new(&path).expect("repo created");
+    fn count(&self, filter: EntryFilter) -> Result<u64, DomainError> {
+        let (where_clause, params) = build_where(&filter);
+        let query = format!("SELECT COUNT(*) FROM entries{where_clause}");
 
-        let entry = repo
-            .add(NewEntry {
-                kind: EntryKind::Expense,
-                amount: usd(1234),
-                category: Category::new("food").unwrap(),
-                note: Some("lunch".to_string()),
-                occurred_on: NaiveDate::from_ymd_opt(2024, 1, 20).expect("date"),
+        let count: i64 = self
+            .conn
+            .query_row(&query, rusqlite::params_from_iter(&params), |row| {
+                row.get(0)
             })
-            .expect("entry added");
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        Ok(count as u64)
+    }
 
-        let entries = repo.list(EntryFilter::default()).expect("entries listed");
+    fn top_entries(&self, filter: EntryFilter, n: usize) -> Result<Vec<Entry>, DomainError> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
 
-        assert_eq!(entries.len(), 1);
-        assert_eq!(entries[0], entry);
+        let (where_clause, params) = build_where(&EntryFilter {
+            kind: Some(EntryKind::Expense),
+            ..filter
+        });
+
+        let query = format!(
+            "SELECT {ENTRY_COLUMNS} FROM entries{where_clause} \
+             ORDER BY amount_cents DESC, occurred_on DESC, id DESC LIMIT {n}"
+        );
 
-        let _ = fs::remove_file(path);
+        self.query_entries(&query, &params)
     }
 
-    #[test]
-    fn list_filters_by_category() {
-        let path = temp_db_path("filter-category");
-        let mut repo = SqliteRepository::new(&path).expect("repo created");
+    fn get_entry(&self, id: EntryId) -> Result<Entry, DomainError> {
+        let entry_row = self
+            .conn
+            .query_row(
+                &format!("SELECT {ENTRY_COLUMNS} FROM entries WHERE id = ?1"),
+                [id.0],
+                EntryRow::from_row,
+            )
+            .optional()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
 
-        repo.add(NewEntry {
-            kind: EntryKind::Expense,
-            amount: usd(500),
-            category: Category::new("food").unwrap(),
-            note: None,
-            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 10).expect("date"),
-        })
-        .expect("entry added");
+        entry_row.ok_or(DomainError::NotFound)?.into_entry()
+    }
 
-        repo.add(NewEntry {
-            kind: EntryKind::Income,
-            amount: usd(2500),
-            category: Category::new("salary").unwrap(),
-            note: None,
-            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 15).expect("date"),
-        })
-        .expect("entry added");
+    fn update_entry(&mut self, entry: Entry) -> Result<Entry, DomainError> {
+        let amount_cents = mapper::from_money(&entry.amount)?;
+        let tags = normalize_tags(entry.tags);
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        let updated = tx
+            .execute(
+                "UPDATE entries
+                 SET kind = ?1, amount_cents = ?2, currency_code = ?3, category = ?4, note = ?5,
+                     occurred_on = ?6, account_id = ?7, version = version + 1
+                 WHERE id = ?8 AND version = ?9 AND archived = 0",
+                params![
+                    mapper::kind_to_str(entry.kind),
+                    amount_cents,
+                    mapper::currency_code(&entry.amount),
+                    entry.category.as_str(),
+                    entry.note,
+                    entry.occurred_on.format(DATE_FORMAT).to_string(),
+                    entry.account.map(|account| account.0),
+                    entry.id.0,
+                    entry.version
+                ],
+            )
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        if updated == 0 {
+            let exists: bool = tx
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM entries WHERE id = ?1 AND archived = 0)",
+                    [entry.id.0],
+                    |row| row.get(0),
+                )
+                .map_err(|err| DomainError::Storage(err.to_string()))?;
+            return Err(if exists {
+                DomainError::Conflict(
+                    "entry was changed elsewhere; reload and try again".to_string(),
+                )
+            } else {
+                DomainError::NotFound
+            });
+        }
 
-        let entries = repo
-            .list(EntryFilter {
-                from: None,
-                to: None,
-                category: Some(Category::new("food").unwrap()),
-            })
-            .expect("entries listed");
+        tx.execute("DELETE FROM entry_tags WHERE entry_id = ?1", [entry.id.0])
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        insert_tags(&tx, entry.id.0, &tags)?;
+        let cleared: bool = tx
+            .query_row(
+                "SELECT cleared FROM entries WHERE id = ?1",
+                [entry.id.0],
+                |row| row.get(0),
+            )
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        tx.commit()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        self.invalidate_categories();
+        log::info!("updated entry {}", entry.id.0);
 
-        assert_eq!(entries.len(), 1);
-        assert_eq!(entries[0].category.as_str(), "food");
+        Ok(Entry {
+            tags,
+            cleared,
+            version: entry.version + 1,
+            ..entry
+        })
+    }
 
-        let _ = fs::remove_file(path);
+    fn set_cleared(&mut self, id: EntryId, cleared: bool) -> Result<(), DomainError> {
+        let changed = self
+            .conn
+            .execute(
+                "UPDATE entries SET cleared = ?1 WHERE id = ?2 AND archived = 0",
+                params![cleared, id.0],
+            )
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        if changed == 0 {
+            return Err(DomainError::NotFound);
+        }
+        log::info!("marked entry {} cleared={cleared}", id.0);
+        Ok(())
     }
 
-    #[test]
-    fn create_and_verify_user() {
-        let path = temp_db_path("user-auth");
-        let mut repo = SqliteRepository::new(&path).expect("repo created");
+    fn delete_entry(&mut self, id: EntryId) -> Result<(), DomainError> {
+        self.set_archived(id, true)?;
+        log::info!("archived entry {}", id.0);
+        Ok(())
+    }
 
-        let user = repo
-            .create_user("alice", "password123")
-            .expect("user created");
+    fn restore_entry(&mut self, id: EntryId) -> Result<(), DomainError> {
+        self.set_archived(id, false)?;
+        log::info!("restored entry {}", id.0);
+        Ok(())
+    }
 
-        assert_eq!(user.username, "alice");
+    fn totals(&self, filter: EntryFilter) -> Result<Totals, DomainError> {
+        let (where_clause, params) = self.sum_where(&filter);
+        let query =
+            format!("SELECT kind, SUM(amount_cents) FROM entries{where_clause} GROUP BY kind");
 
-        let verified = repo.verify_user("alice", "password123").expect("verify ok");
-        assert_eq!(
-            verified.as_ref().map(|u| u.username.as_str()),
-            Some("alice")
+        let mut stmt = self
+            .conn
+            .prepare(&query)
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        let sums = stmt
+            .query_map(rusqlite::params_from_iter(&params), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })
+            .map_err(|err| DomainError::Storage(err.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+        let mut totals = Totals::default();
+        for (kind, sum) in sums {
+            match mapper::kind_from_str(kind)? {
+                EntryKind::Income => totals.income_cents += sum,
+                EntryKind::Expense => totals.expense_cents += sum,
+                EntryKind::Transfer => {}
+            }
+        }
+        Ok(totals)
+    }
+
+    fn monthly_summary(&self, year: i32) -> Result<Vec<MonthlyTotals>, DomainError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT CAST(strftime('%m', occurred_on) AS INTEGER), kind, SUM(amount_cents)
+                 FROM entries
+                 WHERE occurred_on >= ?1 AND occurred_on <= ?2 AND archived = 0
+                   AND currency_code = ?3
+                 GROUP BY 1, kind",
+            )
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+        let sums = stmt
+            .query_map(
+                params![
+                    format!("{year:04}-01-01"),
+                    format!("{year:04}-12-31"),
+                    self.default_currency.iso_alpha_code
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, u32>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, i64>(2)?,
+                    ))
+                },
+            )
+            .map_err(|err| DomainError::Storage(err.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+        let mut months: Vec<MonthlyTotals> = (1..=12)
+            .map(|month| MonthlyTotals {
+                month,
+                ..MonthlyTotals::default()
+            })
+            .collect();
+        for (month, kind, sum) in sums {
+            let Some(totals) = months.get_mut(month.wrapping_sub(1) as usize) else {
+                continue;
+            };
+            match mapper::kind_from_str(kind)? {
+                EntryKind::Income => totals.income_cents += sum,
+                EntryKind::Expense => totals.expense_cents += sum,
+                EntryKind::Transfer => {}
+            }
+        }
+        Ok(months)
+    }
+
+    fn daily_expense_totals(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, i64)>, DomainError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT occurred_on, SUM(amount_cents) FROM entries
+                 WHERE kind = ?1 AND archived = 0 AND occurred_on >= ?2 AND occurred_on <= ?3
+                   AND currency_code = ?4
+                 GROUP BY occurred_on",
+            )
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+        let sums = stmt
+            .query_map(
+                params![
+                    mapper::kind_to_str(EntryKind::Expense),
+                    from.format(DATE_FORMAT).to_string(),
+                    to.format(DATE_FORMAT).to_string(),
+                    self.default_currency.iso_alpha_code
+                ],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+            )
+            .map_err(|err| DomainError::Storage(err.to_string()))?
+            .collect::<Result<HashMap<_, _>, _>>()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+        Ok(from
+            .iter_days()
+            .take_while(|day| *day <= to)
+            .map(|day| {
+                let key = day.format(DATE_FORMAT).to_string();
+                (day, sums.get(&key).copied().unwrap_or(0))
+            })
+            .collect())
+    }
+
+    fn category_breakdown(&self, filter: EntryFilter) -> Result<Vec<CategoryTotal>, DomainError> {
+        let (where_clause, params) = self.sum_where(&filter);
+        let query = format!(
+            "SELECT category, SUM(amount_cents) AS total FROM entries{where_clause} \
+             GROUP BY category ORDER BY total DESC, category"
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(&query)
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        let sums = stmt
+            .query_map(rusqlite::params_from_iter(&params), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })
+            .map_err(|err| DomainError::Storage(err.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+        Ok(sums
+            .into_iter()
+            .map(|(category, total_cents)| CategoryTotal {
+                category: Category::from_stored(category),
+                total_cents,
+            })
+            .collect())
+    }
+
+    fn categories(&self) -> Result<Vec<Category>, DomainError> {
+        let data_version: i64 = self
+            .conn
+            .query_row("PRAGMA data_version", [], |row| row.get(0))
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        if let Some((version, categories)) = self.categories_cache.borrow().as_ref()
+            && *version == data_version
+        {
+            return Ok(categories.clone());
+        }
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT category FROM entries WHERE archived = 0 ORDER BY category")
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+        let names = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|err| DomainError::Storage(err.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+        let categories: Vec<Category> = names.into_iter().map(Category::from_stored).collect();
+
+        *self.categories_cache.borrow_mut() = Some((data_version, categories.clone()));
+        Ok(categories)
+    }
+
+    fn merge_categories(
+        &mut self,
+        sources: Vec<Category>,
+        target: Category,
+    ) -> Result<u64, DomainError> {
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        let mut moved = 0;
+        for source in sources.iter().filter(|source| **source != target) {
+            moved += tx
+                .execute(
+                    "UPDATE entries SET category = ?1, version = version + 1 WHERE category = ?2",
+                    [target.as_str(), source.as_str()],
+                )
+                .map_err(|err| DomainError::Storage(err.to_string()))? as u64;
+        }
+        tx.commit()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        self.invalidate_categories();
+        log::info!("merged {moved} entries into category {}", target.as_str());
+        Ok(moved)
+    }
+
+    fn create_account(&mut self, name: &str) -> Result<Account, DomainError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DomainError::InvalidData(
+                "Account name cannot be empty".to_string(),
+            ));
+        }
+
+        self.conn
+            .execute("INSERT INTO accounts (name) VALUES (?1)", [name])
+            .map_err(|err| match err.sqlite_error_code() {
+                Some(ErrorCode::ConstraintViolation) => {
+                    DomainError::Conflict(format!("account {name} already exists"))
+                }
+                _ => DomainError::Storage(err.to_string()),
+            })?;
+
+        Ok(Account {
+            id: AccountId(self.conn.last_insert_rowid()),
+            name: name.to_string(),
+        })
+    }
+
+    fn list_accounts(&self) -> Result<Vec<Account>, DomainError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, name FROM accounts ORDER BY name")
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+        stmt.query_map([], |row| {
+            Ok(Account {
+                id: AccountId(row.get(0)?),
+                name: row.get(1)?,
+            })
+        })
+        .map_err(|err| DomainError::Storage(err.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| DomainError::Storage(err.to_string()))
+    }
+
+    fn add_recurring(&mut self, rule: RecurringEntry) -> Result<(), DomainError> {
+        let amount_cents = mapper::from_money(&rule.amount)?;
+        self.conn
+            .execute(
+                "INSERT INTO recurring_entries
+                    (kind, amount_cents, currency_code, category, note, frequency, start_date, end_date)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    mapper::kind_to_str(rule.kind),
+                    amount_cents,
+                    mapper::currency_code(&rule.amount),
+                    rule.category.as_str(),
+                    rule.note,
+                    mapper::frequency_to_str(rule.frequency),
+                    rule.start_date.format(DATE_FORMAT).to_string(),
+                    rule.end_date.map(|date| date.format(DATE_FORMAT).to_string()),
+                ],
+            )
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        Ok(())
+    }
+
+    fn expand_due(&mut self, as_of: NaiveDate) -> Result<Vec<Entry>, DomainError> {
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+        let rows = {
+            let mut stmt = tx
+                .prepare(
+                    "SELECT id, kind, amount_cents, currency_code, category, note, frequency,
+                            start_date, end_date, generated_count
+                     FROM recurring_entries ORDER BY id",
+                )
+                .map_err(|err| DomainError::Storage(err.to_string()))?;
+            stmt.query_map([], RecurringRow::from_row)
+                .map_err(|err| DomainError::Storage(err.to_string()))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| DomainError::Storage(err.to_string()))?
+        };
+
+        let mut created = Vec::new();
+        for row in rows {
+            let (id, generated_count) = (row.id, row.generated_count);
+            let rule = row.into_rule()?;
+
+            let mut count = generated_count;
+            while let Some(date) = rule.occurrence(count).filter(|date| *date <= as_of) {
+                created.push(insert_entry(&tx, rule.to_new_entry(date))?);
+                count += 1;
+            }
+            if count != generated_count {
+                tx.execute(
+                    "UPDATE recurring_entries SET generated_count = ?1 WHERE id = ?2",
+                    params![count, id],
+                )
+                .map_err(|err| DomainError::Storage(err.to_string()))?;
+            }
+        }
+
+        tx.commit()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        if !created.is_empty() {
+            self.invalidate_categories();
+        }
+        Ok(created)
+    }
+
+    fn default_currency(&self) -> &'static iso::Currency {
+        self.default_currency
+    }
+}
+
+impl SqliteRepository {
+    /// Looks up a user together with their stored password hash.
+    fn find_user(&self, username: &str) -> Result<Option<(User, String)>, DomainError> {
+        self.conn
+            .query_row(
+                "SELECT id, username, password_hash FROM users WHERE username = ?1",
+                [username],
+                |row| {
+                    let user = User {
+                        id: row.get(0)?,
+                        username: row.get(1)?,
+                    };
+                    Ok((user, row.get::<_, String>(2)?))
+                },
+            )
+            .optional()
+            .map_err(|err| DomainError::Storage(err.to_string()))
+    }
+}
+
+fn hash_password(password: &str) -> Result<String, DomainError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| DomainError::Storage(format!("Hashing failed: {}", e)))
+}
+
+fn password_matches(password: &str, password_hash: &str) -> Result<bool, DomainError> {
+    let parsed_hash = PasswordHash::new(password_hash)
+        .map_err(|e| DomainError::Storage(format!("Invalid hash: {}", e)))?;
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok())
+}
+
+impl UserRepository for SqliteRepository {
+    fn create_user(&mut self, username: &str, password: &str) -> Result<User, DomainError> {
+        validate_password(password)?;
+        let password_hash = hash_password(password)?;
+
+        self.conn
+            .execute(
+                "INSERT INTO users (username, password_hash) VALUES (?1, ?2)",
+                params![username, password_hash],
+            )
+            .map_err(|err| match err.sqlite_error_code() {
+                // `users.username` is UNIQUE (see 002_users.sql).
+                Some(ErrorCode::ConstraintViolation) => {
+                    DomainError::Conflict("username already taken".to_string())
+                }
+                _ => DomainError::Storage(err.to_string()),
+            })?;
+
+        let id = self.conn.last_insert_rowid();
+
+        Ok(User {
+            id,
+            username: username.to_string(),
+        })
+    }
+
+    fn verify_user(&self, username: &str, password: &str) -> Result<Option<User>, DomainError> {
+        if let Some((user, password_hash)) = self.find_user(username)?
+            && password_matches(password, &password_hash)?
+        {
+            return Ok(Some(user));
+        }
+
+        Ok(None)
+    }
+
+    fn change_password(
+        &mut self,
+        username: &str,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<(), DomainError> {
+        let (user, password_hash) = self.find_user(username)?.ok_or(DomainError::NotFound)?;
+        if !password_matches(old_password, &password_hash)? {
+            return Err(DomainError::InvalidData(
+                "Current password is incorrect".to_string(),
+            ));
+        }
+
+        validate_password(new_password)?;
+        let new_hash = hash_password(new_password)?;
+        self.conn
+            .execute(
+                "UPDATE users SET password_hash = ?1 WHERE id = ?2",
+                params![new_hash, user.id],
+            )
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        Ok(())
+    }
+
+    fn delete_user(&mut self, username: &str, password: &str) -> Result<(), DomainError> {
+        let (user, password_hash) = self.find_user(username)?.ok_or(DomainError::NotFound)?;
+        if !password_matches(password, &password_hash)? {
+            return Err(DomainError::InvalidData(
+                "Password is incorrect".to_string(),
+            ));
+        }
+
+        self.conn
+            .execute("DELETE FROM users WHERE id = ?1", [user.id])
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+        Ok(())
+    }
+
+    fn list_users(&self) -> Result<Vec<String>, DomainError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT username FROM users ORDER BY username")
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+        let users = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|err| DomainError::Storage(err.to_string()))?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|err| DomainError::Storage(err.to_string()))?;
+
+        Ok(users)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use domain::{Category, EntryFilter, EntryKind, Frequency, NewEntry};
+    use rusty_money::{Money, iso};
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// A database file in the temp dir, removed together with its WAL and
+    /// shared-memory files when dropped. Bind it before the repository so the
+    /// connection is closed first.
+    struct TempDb(PathBuf);
+
+    impl std::ops::Deref for TempDb {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl AsRef<Path> for TempDb {
+        fn as_ref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            for suffix in ["", "-wal", "-shm"] {
+                let mut path = self.0.clone().into_os_string();
+                path.push(suffix);
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+
+    fn temp_db_path(name: &str) -> TempDb {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time moves forward")
+            .as_nanos();
+        TempDb(std::env::temp_dir().join(format!("tui-money-{name}-{suffix}.db")))
+    }
+
+    fn usd(amount: i64) -> Money<'static, iso::Currency> {
+        Money::from_minor(amount, iso::USD)
+    }
+
+    #[test]
+    fn in_memory_repository_runs_migrations() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        let applied: i64 = repo
+            .conn
+            .query_row("SELECT COUNT(*) FROM schema_migrations", [], |row| {
+                row.get(0)
+            })
+            .expect("migrations counted");
+        assert_eq!(applied, MIGRATIONS.len() as i64);
+
+        let entry = repo
+            .add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(999),
+                category: Category::new("coffee").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 6, 1).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+
+        let entries = repo.list(EntryFilter::default()).expect("entries listed");
+        assert_eq!(entries, [entry]);
+    }
+
+    #[test]
+    fn fresh_database_reports_every_migration() {
+        let repo = SqliteRepository::new_in_memory().expect("repo created");
+        let expected: Vec<&str> = MIGRATIONS.iter().map(|(version, _)| *version).collect();
+        assert_eq!(repo.schema_versions().expect("versions listed"), expected);
+    }
+
+    #[test]
+    fn legacy_categories_are_normalized_and_stay_readable() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let long = "x".repeat(Category::MAX_LEN + 6);
+        for category in ["food", "food ", " food\t\tcourt  ", long.as_str()] {
+            repo.conn
+                .execute(
+                    "INSERT INTO entries (kind, amount_cents, currency_code, category, occurred_on)
+                     VALUES ('expense', 100, 'USD', ?1, '2024-01-01')",
+                    [category],
+                )
+                .expect("raw insert");
+        }
+        // Rows written before the rules existed: run the migration again.
+        repo.conn
+            .execute(
+                "DELETE FROM schema_migrations WHERE version = '011_normalize_categories.sql'",
+                [],
+            )
+            .expect("migration forgotten");
+        repo.run_migrations(MIGRATIONS).expect("migrated");
+
+        let names: Vec<String> = repo
+            .categories()
+            .expect("categories listed")
+            .iter()
+            .map(|category| category.as_str().to_string())
+            .collect();
+        assert_eq!(names, ["food", "food court", long.as_str()]);
+        assert_eq!(repo.list(EntryFilter::default()).expect("listed").len(), 4);
+
+        let food = repo
+            .list(EntryFilter {
+                category: Some(Category::new("food").unwrap()),
+                ..EntryFilter::default()
+            })
+            .expect("filtered");
+        assert_eq!(food.len(), 2);
+        let breakdown = repo
+            .category_breakdown(EntryFilter::default())
+            .expect("breakdown");
+        assert_eq!(breakdown.len(), 3);
+    }
+
+    #[test]
+    fn sums_only_count_the_default_currency() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let day = NaiveDate::from_ymd_opt(2024, 3, 10).expect("date");
+        for (cents, currency) in [(1250, iso::USD), (1500, iso::JPY), (700, iso::EUR)] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: Money::from_minor(cents, currency),
+                category: Category::new("travel").unwrap(),
+                note: None,
+                occurred_on: day,
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let totals = repo.totals(EntryFilter::default()).expect("totals");
+        assert_eq!(totals.expense_cents, 1250);
+        let breakdown = repo
+            .category_breakdown(EntryFilter::default())
+            .expect("breakdown");
+        assert_eq!(breakdown[0].total_cents, 1250);
+        assert_eq!(
+            repo.daily_expense_totals(day, day).expect("daily"),
+            [(day, 1250)]
+        );
+        assert_eq!(
+            repo.monthly_summary(2024).expect("summary")[2].expense_cents,
+            1250
+        );
+
+        let repo = repo.with_default_currency(iso::JPY);
+        let totals = repo.totals(EntryFilter::default()).expect("totals");
+        assert_eq!(totals.expense_cents, 1500);
+    }
+
+    #[test]
+    fn garbage_file_is_rejected_on_open() {
+        let path = temp_db_path("garbage");
+        fs::write(&path, b"this is not sqlite, just some bytes ".repeat(200)).expect("written");
+
+        match SqliteRepository::new(&path) {
+            Err(DomainError::InvalidData(message)) => {
+                assert!(message.contains("not a tui-money database"));
+            }
+            Err(other) => panic!("unexpected error: {other}"),
+            Ok(_) => panic!("garbage file was opened"),
+        }
+    }
+
+    #[test]
+    fn healthy_database_passes_integrity_check() {
+        let repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.check_integrity().expect("database is ok");
+    }
+
+    #[test]
+    fn database_from_a_newer_version_is_rejected() {
+        let path = temp_db_path("newer-schema");
+        let repo = SqliteRepository::new(&path).expect("repo created");
+        repo.conn
+            .execute(
+                "INSERT INTO schema_migrations (version) VALUES ('999_future.sql')",
+                [],
+            )
+            .expect("future version recorded");
+        drop(repo);
+
+        match SqliteRepository::new(&path) {
+            Err(DomainError::InvalidData(message)) => {
+                assert!(message.starts_with("database is newer than this app"));
+                assert!(message.contains("999_future.sql"));
+            }
+            Err(other) => panic!("unexpected error: {other}"),
+            Ok(_) => panic!("newer database was opened"),
+        }
+    }
+
+    #[test]
+    fn two_repositories_can_write_to_the_same_file() {
+        let path = temp_db_path("concurrent");
+        let first = SqliteRepository::new(&path).expect("first repo created");
+        let second = SqliteRepository::new(&path).expect("second repo created");
+
+        let journal_mode: String = first
+            .conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .expect("journal mode");
+        assert_eq!(journal_mode.to_lowercase(), "wal");
+
+        let writers: Vec<_> = [first, second]
+            .into_iter()
+            .enumerate()
+            .map(|(writer, mut repo)| {
+                std::thread::spawn(move || {
+                    for i in 0..20 {
+                        repo.add(NewEntry {
+                            kind: EntryKind::Expense,
+                            amount: usd(100 + i),
+                            category: Category::new(format!("writer-{writer}")).unwrap(),
+                            note: None,
+                            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 1).expect("date"),
+                            account: None,
+                            tags: Vec::new(),
+                        })
+                        .expect("write without lock error");
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().expect("writer finished");
+        }
+
+        let repo = SqliteRepository::new(&path).expect("repo reopened");
+        let entries = repo.list(EntryFilter::default()).expect("entries listed");
+        assert_eq!(entries.len(), 40);
+    }
+
+    #[test]
+    fn index_migration_is_recorded() {
+        let repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        let recorded: i64 = repo
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM schema_migrations WHERE version = '004_indexes.sql'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("migration looked up");
+        assert_eq!(recorded, 1);
+
+        let mut stmt = repo
+            .conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'index' AND tbl_name = 'entries'")
+            .expect("statement prepared");
+        let indexes: Vec<String> = stmt
+            .query_map([], |row| row.get(0))
+            .expect("indexes listed")
+            .collect::<Result<_, _>>()
+            .expect("index names");
+        assert!(indexes.contains(&"idx_entries_occurred_on".to_string()));
+        assert!(indexes.contains(&"idx_entries_category".to_string()));
+    }
+
+    #[test]
+    fn add_and_list_entries() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        let entry = repo
+            .add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(1234),
+                category: Category::new("food").unwrap(),
+                note: Some("lunch".to_string()),
+                occurred_on: NaiveDate::from_ymd_opt(2024, 1, 20).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+
+        let entries = repo.list(EntryFilter::default()).expect("entries listed");
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0], entry);
+    }
+
+    #[test]
+    fn list_filters_by_category() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        repo.add(NewEntry {
+            kind: EntryKind::Expense,
+            amount: usd(500),
+            category: Category::new("food").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 10).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+
+        repo.add(NewEntry {
+            kind: EntryKind::Income,
+            amount: usd(2500),
+            category: Category::new("salary").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 15).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+
+        let entries = repo
+            .list(EntryFilter {
+                category: Some(Category::new("food").unwrap()),
+                ..EntryFilter::default()
+            })
+            .expect("entries listed");
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].category.as_str(), "food");
+    }
+
+    #[test]
+    fn list_filters_by_kind() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        repo.add(NewEntry {
+            kind: EntryKind::Expense,
+            amount: usd(700),
+            category: Category::new("food").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 10).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+
+        repo.add(NewEntry {
+            kind: EntryKind::Income,
+            amount: usd(3000),
+            category: Category::new("salary").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 15).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+
+        let income = repo
+            .list(EntryFilter {
+                kind: Some(EntryKind::Income),
+                ..EntryFilter::default()
+            })
+            .expect("entries listed");
+        assert_eq!(income.len(), 1);
+        assert_eq!(income[0].kind, EntryKind::Income);
+
+        let all = repo.list(EntryFilter::default()).expect("entries listed");
+        assert_eq!(all.len(), 2);
+    }
+
+    #[test]
+    fn list_filters_by_amount_range() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        for cents in [100, 500, 1000] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(cents),
+                category: Category::new("misc").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 1, 10).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let entries = repo
+            .list(EntryFilter {
+                min_cents: Some(400),
+                max_cents: Some(900),
+                ..EntryFilter::default()
+            })
+            .expect("entries listed");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].amount, usd(500));
+
+        let combined = repo
+            .list(EntryFilter {
+                min_cents: Some(400),
+                category: Some(Category::new("other").unwrap()),
+                ..EntryFilter::default()
+            })
+            .expect("entries listed");
+        assert!(combined.is_empty());
+    }
+
+    #[test]
+    fn list_filters_by_note_substring() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        for note in [Some("Uber to airport"), Some("50% off lunch"), None] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(1000),
+                category: Category::new("travel").unwrap(),
+                note: note.map(str::to_string),
+                occurred_on: NaiveDate::from_ymd_opt(2024, 1, 10).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let search = |needle: &str| {
+            repo.list(EntryFilter {
+                note_contains: Some(needle.to_string()),
+                ..EntryFilter::default()
+            })
+            .expect("entries listed")
+        };
+
+        let matched = search("uber");
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].note.as_deref(), Some("Uber to airport"));
+
+        assert!(search("taxi").is_empty());
+
+        let literal = search("0%");
+        assert_eq!(literal.len(), 1);
+        assert_eq!(literal[0].note.as_deref(), Some("50% off lunch"));
+        assert!(search("_").is_empty());
+    }
+
+    #[test]
+    fn list_pages_with_limit_and_offset() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        for day in 1..=6 {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(100 * day),
+                category: Category::new("misc").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 1, day as u32).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let all = repo.list(EntryFilter::default()).expect("entries listed");
+        assert_eq!(all.len(), 6);
+
+        let pages: Vec<Vec<Entry>> = (0..3)
+            .map(|page| {
+                repo.list(EntryFilter {
+                    limit: Some(2),
+                    offset: Some(page * 2),
+                    ..EntryFilter::default()
+                })
+                .expect("page listed")
+            })
+            .collect();
+
+        for (page, entries) in pages.iter().enumerate() {
+            assert_eq!(entries.as_slice(), &all[page * 2..page * 2 + 2]);
+        }
+
+        let tail = repo
+            .list(EntryFilter {
+                offset: Some(5),
+                ..EntryFilter::default()
+            })
+            .expect("tail listed");
+        assert_eq!(tail.as_slice(), &all[5..]);
+    }
+
+    #[test]
+    fn list_honors_sort_order() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        for (day, cents) in [(1, 800), (2, 200), (3, 500)] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(cents),
+                category: Category::new("misc").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 1, day).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let sorted = |sort: EntrySort| {
+            repo.list(EntryFilter {
+                sort,
+                ..EntryFilter::default()
+            })
+            .expect("entries listed")
+        };
+
+        let first_day = |entries: Vec<Entry>| entries[0].occurred_on.format("%d").to_string();
+        assert_eq!(first_day(sorted(EntrySort::DateDesc)), "03");
+        assert_eq!(first_day(sorted(EntrySort::DateAsc)), "01");
+        assert_eq!(sorted(EntrySort::AmountDesc)[0].amount, usd(800));
+        assert_eq!(sorted(EntrySort::AmountAsc)[0].amount, usd(200));
+    }
+
+    #[test]
+    fn totals_sum_by_kind() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        for (kind, cents, day) in [
+            (EntryKind::Income, 250000, 1),
+            (EntryKind::Expense, 1250, 2),
+            (EntryKind::Expense, 4000, 3),
+            (EntryKind::Income, 1500, 20),
+        ] {
+            repo.add(NewEntry {
+                kind,
+                amount: usd(cents),
+                category: Category::new("misc").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 1, day).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let totals = repo.totals(EntryFilter::default()).expect("totals");
+        assert_eq!(
+            totals,
+            Totals {
+                income_cents: 251500,
+                expense_cents: 5250,
+            }
+        );
+        assert_eq!(totals.net_cents(), 246250);
+
+        let early = repo
+            .totals(EntryFilter {
+                to: NaiveDate::from_ymd_opt(2024, 1, 2),
+                ..EntryFilter::default()
+            })
+            .expect("totals");
+        assert_eq!(early.income_cents, 250000);
+        assert_eq!(early.expense_cents, 1250);
+
+        repo.add(NewEntry {
+            kind: EntryKind::Transfer,
+            amount: usd(70000),
+            category: Category::new("savings").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 5).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("transfer added");
+        let with_transfer = repo.totals(EntryFilter::default()).expect("totals");
+        assert_eq!(with_transfer, totals);
+
+        let empty = repo
+            .totals(EntryFilter {
+                from: NaiveDate::from_ymd_opt(2025, 1, 1),
+                ..EntryFilter::default()
+            })
+            .expect("totals");
+        assert_eq!(empty, Totals::default());
+    }
+
+    #[test]
+    fn get_entry_by_id() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        let entry = repo
+            .add(NewEntry {
+                kind: EntryKind::Income,
+                amount: usd(4200),
+                category: Category::new("salary").unwrap(),
+                note: Some("bonus".to_string()),
+                occurred_on: NaiveDate::from_ymd_opt(2024, 2, 1).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+
+        let fetched = repo.get_entry(entry.id).expect("entry fetched");
+        assert_eq!(fetched, entry);
+
+        let missing = repo.get_entry(EntryId(entry.id.0 + 1));
+        assert!(matches!(missing, Err(DomainError::NotFound)));
+    }
+
+    #[test]
+    fn delete_entry_archives_row() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let entry = repo
+            .add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(1200),
+                category: Category::new("books").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 2, 1).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        assert_eq!(repo.categories().unwrap().len(), 1);
+
+        repo.delete_entry(entry.id).expect("entry deleted");
+        assert!(repo.list(EntryFilter::default()).unwrap().is_empty());
+        assert!(repo.categories().unwrap().is_empty());
+
+        // The row is only archived, so it can still be listed and restored.
+        let archived = repo
+            .list(EntryFilter {
+                include_archived: true,
+                ..EntryFilter::default()
+            })
+            .unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0], entry);
+
+        let again = repo.delete_entry(entry.id);
+        assert!(matches!(again, Err(DomainError::NotFound)));
+    }
+
+    #[test]
+    fn archived_entries_are_hidden_unless_requested() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut add = |category: &str| {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(700),
+                category: Category::new(category).unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 2, 1).expect("date"),
+                account: None,
+                tags: vec!["trip".to_string()],
+            })
+            .expect("entry added")
+        };
+        let kept = add("food");
+        let archived = add("taxi");
+
+        repo.delete_entry(archived.id).expect("entry archived");
+        let visible = repo.list(EntryFilter::default()).unwrap();
+        assert_eq!(
+            visible.iter().map(|entry| entry.id).collect::<Vec<_>>(),
+            [kept.id]
+        );
+        assert_eq!(repo.count(EntryFilter::default()).unwrap(), 1);
+        assert_eq!(
+            repo.totals(EntryFilter::default()).unwrap().expense_cents,
+            700
+        );
+
+        let all = repo
+            .list(EntryFilter {
+                include_archived: true,
+                ..EntryFilter::default()
+            })
+            .unwrap();
+        assert_eq!(all.len(), 2);
+        let trash = all.iter().find(|entry| entry.id == archived.id).unwrap();
+        assert_eq!(trash.tags, ["trip"]);
+
+        repo.restore_entry(archived.id).expect("entry restored");
+        assert_eq!(repo.list(EntryFilter::default()).unwrap().len(), 2);
+        assert!(matches!(
+            repo.restore_entry(archived.id),
+            Err(DomainError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn stale_update_is_a_conflict() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let entry = repo
+            .add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(1200),
+                category: Category::new("food").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 2, 1).expect("date"),
+                account: None,
+                tags: vec!["lunch".to_string()],
+            })
+            .expect("entry added");
+        assert_eq!(entry.version, 1);
+
+        // Two copies read at version 1; the first save wins.
+        let first = Entry {
+            amount: usd(1500),
+            tags: vec!["team".to_string()],
+            ..entry.clone()
+        };
+        let second = Entry {
+            note: Some("from the other window".to_string()),
+            ..entry.clone()
+        };
+        let saved = repo.update_entry(first).expect("entry updated");
+        assert_eq!(saved.version, 2);
+        assert_eq!(repo.get_entry(entry.id).unwrap(), saved);
+
+        let stale = repo.update_entry(second);
+        assert!(matches!(stale, Err(DomainError::Conflict(_))));
+        assert_eq!(repo.get_entry(entry.id).unwrap().amount, usd(1500));
+
+        repo.delete_entry(entry.id).expect("entry archived");
+        assert!(matches!(
+            repo.update_entry(saved),
+            Err(DomainError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn top_entries_orders_expenses_by_amount() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        for (kind, cents, category) in [
+            (EntryKind::Expense, 1500, "food"),
+            (EntryKind::Expense, 9900, "rent"),
+            (EntryKind::Income, 50000, "salary"),
+            (EntryKind::Expense, 4000, "travel"),
+        ] {
+            repo.add(NewEntry {
+                kind,
+                amount: usd(cents),
+                category: Category::new(category).unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 3, 5).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let top = repo
+            .top_entries(EntryFilter::default(), 2)
+            .expect("top entries");
+        let categories: Vec<&str> = top.iter().map(|e| e.category.as_str()).collect();
+        assert_eq!(categories, ["rent", "travel"]);
+
+        let all = repo
+            .top_entries(EntryFilter::default(), 10)
+            .expect("top entries");
+        assert_eq!(all.len(), 3);
+        assert!(all.iter().all(|e| e.kind == EntryKind::Expense));
+
+        let none = repo
+            .top_entries(EntryFilter::default(), 0)
+            .expect("top entries");
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn entries_keep_their_currency() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        repo.add(NewEntry {
+            kind: EntryKind::Expense,
+            amount: Money::from_minor(1500, iso::JPY),
+            category: Category::new("sushi").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 4, 2).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("yen entry added");
+        repo.add(NewEntry {
+            kind: EntryKind::Expense,
+            amount: usd(1250),
+            category: Category::new("books").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 4, 1).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("dollar entry added");
+
+        let entries = repo.list(EntryFilter::default()).expect("entries listed");
+
+        assert_eq!(entries[0].amount, Money::from_minor(1500, iso::JPY));
+        assert_eq!(entries[0].amount.currency().iso_alpha_code, "JPY");
+        assert_eq!(entries[1].amount, usd(1250));
+        assert_eq!(entries[1].amount.currency().iso_alpha_code, "USD");
+    }
+
+    #[test]
+    fn daily_expense_totals_cover_a_week() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        for (cents, day) in [(500, 2), (250, 2), (1000, 5), (9900, 9)] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(cents),
+                category: Category::new("food").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 4, day).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let from = NaiveDate::from_ymd_opt(2024, 4, 1).expect("date");
+        let to = NaiveDate::from_ymd_opt(2024, 4, 7).expect("date");
+        let week = repo.daily_expense_totals(from, to).expect("totals");
+        assert_eq!(week.len(), 7);
+        assert_eq!(week[0].0, from);
+        assert_eq!(week[6].0, to);
+        assert_eq!(
+            week.iter().map(|(_, cents)| *cents).collect::<Vec<_>>(),
+            [0, 750, 0, 0, 1000, 0, 0]
+        );
+
+        assert!(repo.daily_expense_totals(to, from).unwrap().is_empty());
+    }
+
+    #[test]
+    fn daily_expense_totals_skip_income_and_transfers() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        for (kind, cents, day) in [
+            (EntryKind::Expense, 1200, 10),
+            (EntryKind::Income, 50000, 10),
+            (EntryKind::Transfer, 3000, 11),
+            (EntryKind::Expense, 300, 13),
+            (EntryKind::Expense, 450, 13),
+            (EntryKind::Expense, 800, 15),
+        ] {
+            repo.add(NewEntry {
+                kind,
+                amount: usd(cents),
+                category: Category::new("misc").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 8, day).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let day = |d| NaiveDate::from_ymd_opt(2024, 8, d).expect("date");
+        let totals = repo.daily_expense_totals(day(10), day(14)).expect("totals");
+        assert_eq!(
+            totals,
+            [
+                (day(10), 1200),
+                (day(11), 0),
+                (day(12), 0),
+                (day(13), 750),
+                (day(14), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_summary_fills_empty_months() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        for (kind, cents, (year, month, day)) in [
+            (EntryKind::Income, 300000, (2024, 1, 1)),
+            (EntryKind::Expense, 1500, (2024, 1, 15)),
+            (EntryKind::Expense, 2500, (2024, 3, 31)),
+            (EntryKind::Expense, 9900, (2023, 2, 10)),
+        ] {
+            repo.add(NewEntry {
+                kind,
+                amount: usd(cents),
+                category: Category::new("misc").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(year, month, day).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let summary = repo.monthly_summary(2024).expect("summary");
+        assert_eq!(summary.len(), 12);
+        assert_eq!(
+            summary[0],
+            MonthlyTotals {
+                month: 1,
+                income_cents: 300000,
+                expense_cents: 1500,
+            }
+        );
+        assert_eq!(
+            summary[1],
+            MonthlyTotals {
+                month: 2,
+                income_cents: 0,
+                expense_cents: 0,
+            }
+        );
+        assert_eq!(summary[2].expense_cents, 2500);
+        assert!(
+            summary[3..]
+                .iter()
+                .all(|m| m.income_cents == 0 && m.expense_cents == 0)
+        );
+    }
+
+    #[test]
+    fn build_where_covers_every_filter_field() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1);
+        let cases = [
+            (EntryFilter::default(), " WHERE archived = 0"),
+            (
+                EntryFilter {
+                    include_archived: true,
+                    ..EntryFilter::default()
+                },
+                "",
+            ),
+            (
+                EntryFilter {
+                    from: date,
+                    ..EntryFilter::default()
+                },
+                " WHERE archived = 0 AND occurred_on >= ?",
+            ),
+            (
+                EntryFilter {
+                    to: date,
+                    ..EntryFilter::default()
+                },
+                " WHERE archived = 0 AND occurred_on <= ?",
+            ),
+            (
+                EntryFilter {
+                    category: Some(Category::new("food").unwrap()),
+                    ..EntryFilter::default()
+                },
+                " WHERE archived = 0 AND category = ?",
+            ),
+            (
+                EntryFilter {
+                    account: Some(AccountId(3)),
+                    ..EntryFilter::default()
+                },
+                " WHERE archived = 0 AND account_id = ?",
+            ),
+            (
+                EntryFilter {
+                    tag: Some("trip".to_string()),
+                    ..EntryFilter::default()
+                },
+                " WHERE archived = 0 AND id IN (SELECT et.entry_id FROM entry_tags et \
+                 JOIN tags t ON t.id = et.tag_id WHERE t.name = ?)",
+            ),
+            (
+                EntryFilter {
+                    kind: Some(EntryKind::Income),
+                    ..EntryFilter::default()
+                },
+                " WHERE archived = 0 AND kind = ?",
+            ),
+            (
+                EntryFilter {
+                    only_uncleared: true,
+                    ..EntryFilter::default()
+                },
+                " WHERE archived = 0 AND cleared = 0",
+            ),
+            (
+                EntryFilter {
+                    min_cents: Some(100),
+                    max_cents: Some(900),
+                    ..EntryFilter::default()
+                },
+                " WHERE archived = 0 AND amount_cents >= ? AND amount_cents <= ?",
+            ),
+            (
+                EntryFilter {
+                    note_contains: Some("lunch".to_string()),
+                    ..EntryFilter::default()
+                },
+                " WHERE archived = 0 AND note LIKE '%' || ? || '%' ESCAPE '\\'",
+            ),
+            (
+                EntryFilter {
+                    note_contains: Some(String::new()),
+                    limit: Some(10),
+                    offset: Some(20),
+                    sort: EntrySort::AmountAsc,
+                    ..EntryFilter::default()
+                },
+                " WHERE archived = 0",
+            ),
+            (
+                EntryFilter {
+                    from: date,
+                    to: date,
+                    category: Some(Category::new("food").unwrap()),
+                    kind: Some(EntryKind::Expense),
+                    ..EntryFilter::default()
+                },
+                " WHERE archived = 0 AND occurred_on >= ? AND occurred_on <= ? AND category = ? AND kind = ?",
+            ),
+        ];
+
+        for (filter, expected) in cases {
+            let (clause, params) = build_where(&filter);
+            assert_eq!(clause, expected);
+            assert_eq!(params.len(), clause.matches('?').count(), "{clause}");
+        }
+
+        let (_, params) = build_where(&EntryFilter {
+            account: Some(AccountId(3)),
+            min_cents: Some(100),
+            note_contains: Some("50%".to_string()),
+            ..EntryFilter::default()
+        });
+        assert_eq!(
+            params,
+            [
+                Value::Integer(3),
+                Value::Integer(100),
+                Value::Text("50\\%".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn count_matches_list_length() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        for (category, day) in [("food", 1), ("food", 5), ("rent", 5), ("fun", 20)] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(100 * day as i64),
+                category: Category::new(category).unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 3, day).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let filters = [
+            EntryFilter::default(),
+            EntryFilter {
+                category: Some(Category::new("food").unwrap()),
+                ..EntryFilter::default()
+            },
+            EntryFilter {
+                from: NaiveDate::from_ymd_opt(2024, 3, 5),
+                to: NaiveDate::from_ymd_opt(2024, 3, 10),
+                ..EntryFilter::default()
+            },
+            EntryFilter {
+                category: Some(Category::new("food").unwrap()),
+                from: NaiveDate::from_ymd_opt(2024, 3, 2),
+                ..EntryFilter::default()
+            },
+            EntryFilter {
+                category: Some(Category::new("travel").unwrap()),
+                ..EntryFilter::default()
+            },
+        ];
+        for filter in filters {
+            let listed = repo.list(filter.clone()).expect("entries listed").len() as u64;
+            assert_eq!(repo.count(filter).expect("entries counted"), listed);
+        }
+
+        let page = EntryFilter {
+            limit: Some(1),
+            offset: Some(1),
+            ..EntryFilter::default()
+        };
+        assert_eq!(repo.count(page).expect("entries counted"), 4);
+    }
+
+    #[test]
+    fn category_breakdown_sorts_by_total() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        for (kind, cents, category, day) in [
+            (EntryKind::Expense, 1200, "food", 2),
+            (EntryKind::Expense, 90000, "rent", 1),
+            (EntryKind::Expense, 800, "food", 9),
+            (EntryKind::Expense, 4500, "travel", 12),
+            (EntryKind::Income, 250000, "salary", 1),
+            (EntryKind::Expense, 7000, "travel", 28),
+        ] {
+            repo.add(NewEntry {
+                kind,
+                amount: usd(cents),
+                category: Category::new(category).unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 5, day).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let breakdown = repo
+            .category_breakdown(EntryFilter {
+                kind: Some(EntryKind::Expense),
+                to: NaiveDate::from_ymd_opt(2024, 5, 20),
+                ..EntryFilter::default()
+            })
+            .expect("breakdown");
+        let rows: Vec<(&str, i64)> = breakdown
+            .iter()
+            .map(|row| (row.category.as_str(), row.total_cents))
+            .collect();
+        assert_eq!(rows, [("rent", 90000), ("travel", 4500), ("food", 2000)]);
+    }
+
+    #[test]
+    fn tags_are_stored_and_filterable() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let tagged = repo
+            .add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(4200),
+                category: Category::new("travel").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 3, 4).expect("date"),
+                account: None,
+                tags: vec!["work".to_string(), " reimbursable ".to_string()],
+            })
+            .expect("entry added");
+        assert_eq!(tagged.tags, ["reimbursable", "work"]);
+        repo.add(NewEntry {
+            kind: EntryKind::Expense,
+            amount: usd(900),
+            category: Category::new("food").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 3, 5).expect("date"),
+            account: None,
+            tags: vec!["personal".to_string()],
+        })
+        .expect("entry added");
+
+        let fetched = repo.get_entry(tagged.id).expect("entry fetched");
+        assert_eq!(fetched.tags, ["reimbursable", "work"]);
+
+        let work = repo
+            .list(EntryFilter {
+                tag: Some("work".to_string()),
+                ..EntryFilter::default()
+            })
+            .expect("entries listed");
+        assert_eq!(work.len(), 1);
+        assert_eq!(work[0].id, tagged.id);
+
+        // Archiving keeps the tags (for a restore) but hides the entry.
+        repo.delete_entry(tagged.id).expect("entry deleted");
+        let count: i64 = repo
+            .conn
+            .query_row("SELECT COUNT(*) FROM entry_tags", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(count, 3);
+        let work = repo
+            .list(EntryFilter {
+                tag: Some("work".to_string()),
+                ..EntryFilter::default()
+            })
+            .expect("entries listed");
+        assert!(work.is_empty());
+    }
+
+    #[test]
+    fn backup_copies_the_open_database() {
+        let path = temp_db_path("backup-src");
+        let dest = temp_db_path("backup-dest");
+        let mut repo = SqliteRepository::new(&path).expect("repo created");
+        for (category, day) in [("food", 1), ("rent", 2)] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(1000),
+                category: Category::new(category).unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 4, day).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        // The second backup overwrites the first.
+        repo.backup_to(&dest).expect("backup written");
+        repo.add(NewEntry {
+            kind: EntryKind::Income,
+            amount: usd(5000),
+            category: Category::new("salary").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 4, 3).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+        repo.backup_to(&dest).expect("backup written");
+
+        let copy = SqliteRepository::new(&dest).expect("backup opened");
+        let entries = copy.list(EntryFilter::default()).expect("entries listed");
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].category.as_str(), "salary");
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn encrypted_database_needs_the_right_key() {
+        let path = temp_db_path("encrypted");
+        {
+            let mut repo = SqliteRepository::new_encrypted(&path, "correct horse").expect("repo");
+            repo.create_user("alice", "secret-42")
+                .expect("user created");
+        }
+
+        let repo = SqliteRepository::new_encrypted(&path, "correct horse").expect("reopened");
+        assert_eq!(repo.list_users().expect("users").len(), 1);
+        drop(repo);
+
+        let result = SqliteRepository::new_encrypted(&path, "battery staple");
+        assert!(matches!(result, Err(DomainError::InvalidData(_))));
+        assert!(SqliteRepository::new(&path).is_err());
+    }
+
+    #[test]
+    fn accounts_are_created_and_listed() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let savings = repo.create_account("savings").expect("account created");
+        let checking = repo.create_account("checking").expect("account created");
+
+        let accounts = repo.list_accounts().expect("accounts listed");
+        assert_eq!(accounts, [checking, savings]);
+
+        let duplicate = repo.create_account("savings");
+        assert!(matches!(duplicate, Err(DomainError::Conflict(_))));
+        let blank = repo.create_account("  ");
+        assert!(matches!(blank, Err(DomainError::InvalidData(_))));
+    }
+
+    #[test]
+    fn entries_filter_by_account() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let checking = repo.create_account("checking").expect("account created");
+        let savings = repo.create_account("savings").expect("account created");
+
+        for (account, cents) in [
+            (Some(checking.id), 100),
+            (Some(savings.id), 200),
+            (None, 300),
+        ] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(cents),
+                category: Category::new("misc").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 5, 1).expect("date"),
+                account,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let in_checking = repo
+            .list(EntryFilter {
+                account: Some(checking.id),
+                ..EntryFilter::default()
+            })
+            .expect("entries listed");
+        assert_eq!(in_checking.len(), 1);
+        assert_eq!(in_checking[0].account, Some(checking.id));
+        assert_eq!(in_checking[0].amount, usd(100));
+
+        // Entries without an account still show up in the unfiltered list.
+        let all = repo.list(EntryFilter::default()).expect("entries listed");
+        assert_eq!(all.len(), 3);
+        assert!(all.iter().any(|entry| entry.account.is_none()));
+    }
+
+    #[test]
+    fn monthly_rule_expands_once_per_month() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.add_recurring(RecurringEntry {
+            kind: EntryKind::Expense,
+            amount: usd(120000),
+            category: Category::new("rent").unwrap(),
+            note: Some("flat".to_string()),
+            frequency: Frequency::Monthly,
+            start_date: NaiveDate::from_ymd_opt(2024, 1, 31).expect("date"),
+            end_date: None,
+        })
+        .expect("rule added");
+
+        let as_of = NaiveDate::from_ymd_opt(2024, 3, 31).expect("date");
+        let created = repo.expand_due(as_of).expect("expanded");
+        let dates: Vec<_> = created.iter().map(|entry| entry.occurred_on).collect();
+        assert_eq!(
+            dates,
+            [
+                NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            ]
+        );
+        assert!(created.iter().all(|entry| entry.amount == usd(120000)));
+
+        // Already recorded occurrences are not repeated.
+        assert!(repo.expand_due(as_of).expect("expanded").is_empty());
+        assert_eq!(repo.list(EntryFilter::default()).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn recurring_rule_stops_at_end_date() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.add_recurring(RecurringEntry {
+            kind: EntryKind::Income,
+            amount: usd(5000),
+            category: Category::new("allowance").unwrap(),
+            note: None,
+            frequency: Frequency::Weekly,
+            start_date: NaiveDate::from_ymd_opt(2024, 1, 1).expect("date"),
+            end_date: NaiveDate::from_ymd_opt(2024, 1, 20),
+        })
+        .expect("rule added");
+
+        let created = repo
+            .expand_due(NaiveDate::from_ymd_opt(2024, 12, 31).expect("date"))
+            .expect("expanded");
+        assert_eq!(created.len(), 3);
+        assert_eq!(repo.categories().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn cleared_flag_persists_and_can_be_filtered_out() {
+        let path = temp_db_path("cleared");
+        let mut repo = SqliteRepository::new(&path).expect("repo created");
+        let mut ids = Vec::new();
+        for day in 1..=3 {
+            let entry = repo
+                .add(NewEntry {
+                    kind: EntryKind::Expense,
+                    amount: usd(700),
+                    category: Category::new("fuel").unwrap(),
+                    note: None,
+                    occurred_on: NaiveDate::from_ymd_opt(2024, 5, day).expect("date"),
+                    account: None,
+                    tags: Vec::new(),
+                })
+                .expect("entry added");
+            assert!(!entry.cleared);
+            ids.push(entry.id);
+        }
+        repo.set_cleared(ids[0], true).expect("marked cleared");
+        repo.set_cleared(ids[2], true).expect("marked cleared");
+        repo.set_cleared(ids[2], false).expect("marked uncleared");
+        drop(repo);
+
+        let mut repo = SqliteRepository::new(&path).expect("repo reopened");
+        let cleared: Vec<bool> = ids
+            .iter()
+            .map(|id| repo.get_entry(*id).expect("entry loaded").cleared)
+            .collect();
+        assert_eq!(cleared, [true, false, false]);
+        assert_eq!(repo.get_entry(ids[0]).unwrap().version, 1);
+
+        let uncleared = repo
+            .list(EntryFilter {
+                only_uncleared: true,
+                sort: EntrySort::DateAsc,
+                ..EntryFilter::default()
+            })
+            .expect("entries listed");
+        let uncleared: Vec<EntryId> = uncleared.iter().map(|entry| entry.id).collect();
+        assert_eq!(uncleared, [ids[1], ids[2]]);
+
+        // Editing an entry keeps its reconciled mark.
+        let edited = repo
+            .update_entry(Entry {
+                note: Some("full tank".to_string()),
+                cleared: false,
+                ..repo.get_entry(ids[0]).unwrap()
+            })
+            .expect("entry updated");
+        assert!(edited.cleared);
+
+        repo.delete_entry(ids[1]).expect("entry archived");
+        assert!(matches!(
+            repo.set_cleared(ids[1], true),
+            Err(DomainError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn merge_categories_moves_entries_into_target() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        for category in ["food", "groceries", "groceries", "rent"] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(500),
+                category: Category::new(category).unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 4, 1).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+        // Warm the cache so the merge has to invalidate it.
+        assert_eq!(repo.categories().unwrap().len(), 3);
+
+        let moved = repo
+            .merge_categories(
+                vec![
+                    Category::new("food").unwrap(),
+                    Category::new("groceries").unwrap(),
+                ],
+                Category::new("eating").unwrap(),
+            )
+            .expect("categories merged");
+        assert_eq!(moved, 3);
+
+        let names: Vec<String> = repo
+            .categories()
+            .unwrap()
+            .iter()
+            .map(|category| category.as_str().to_string())
+            .collect();
+        assert_eq!(names, ["eating", "rent"]);
+        let eating = repo
+            .list(EntryFilter {
+                category: Some(Category::new("eating").unwrap()),
+                ..EntryFilter::default()
+            })
+            .unwrap();
+        assert!(eating.iter().all(|entry| entry.version == 2));
+    }
+
+    #[test]
+    fn categories_are_distinct_and_sorted() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        for category in ["rent", "food", "food"] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(100),
+                category: Category::new(category).unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 5, 1).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let categories = repo.categories().expect("categories listed");
+        let names: Vec<&str> = categories.iter().map(Category::as_str).collect();
+        assert_eq!(names, ["food", "rent"]);
+    }
+
+    #[test]
+    fn adding_new_category_invalidates_cache() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        repo.add(NewEntry {
+            kind: EntryKind::Expense,
+            amount: usd(300),
+            category: Category::new("food").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 5, 1).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+
+        let cached = repo.categories().expect("categories listed");
+        assert_eq!(cached, [Category::new("food").unwrap()]);
+        assert!(repo.categories_cache.borrow().is_some());
+
+        repo.add(NewEntry {
+            kind: EntryKind::Expense,
+            amount: usd(900),
+            category: Category::new("rent").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 5, 2).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+
+        let refreshed = repo.categories().expect("categories listed");
+        assert_eq!(
+            refreshed,
+            [
+                Category::new("food").unwrap(),
+                Category::new("rent").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn categories_cache_sees_writes_from_other_connections() {
+        let path = temp_db_path("categories-other");
+        let reader = SqliteRepository::new(&path).expect("repo created");
+        let mut writer = SqliteRepository::new(&path).expect("repo created");
+        assert!(reader.categories().expect("categories listed").is_empty());
+
+        writer
+            .add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: usd(300),
+                category: Category::new("food").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 5, 1).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+
+        assert_eq!(
+            reader.categories().expect("categories listed"),
+            [Category::new("food").unwrap()]
+        );
+    }
+
+    #[test]
+    fn create_and_verify_user() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+
+        let user = repo
+            .create_user("alice", "password123")
+            .expect("user created");
+
+        assert_eq!(user.username, "alice");
+
+        let verified = repo.verify_user("alice", "password123").expect("verify ok");
+        assert_eq!(
+            verified.as_ref().map(|u| u.username.as_str()),
+            Some("alice")
         );
 
         let wrong_pass = repo
@@ -375,7 +2747,118 @@ mod tests {
         // List users
         let users = repo.list_users().expect("list users");
         assert!(users.contains(&"alice".to_string()));
+    }
+
+    #[test]
+    fn duplicate_username_is_a_conflict() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.create_user("alice", "password123")
+            .expect("user created");
+
+        let duplicate = repo.create_user("alice", "other-pw1");
+        assert!(matches!(duplicate, Err(DomainError::Conflict(_))));
+        assert_eq!(repo.list_users().unwrap(), ["alice"]);
+    }
+
+    #[test]
+    fn weak_passwords_are_rejected() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let weak = repo.create_user("alice", "a");
+        assert!(matches!(weak, Err(DomainError::InvalidData(_))));
+        assert!(repo.list_users().unwrap().is_empty());
+
+        repo.create_user("alice", "password123")
+            .expect("user created");
+        let weak = repo.change_password("alice", "password123", "password");
+        assert!(matches!(weak, Err(DomainError::InvalidData(_))));
+        assert!(repo.verify_user("alice", "password123").unwrap().is_some());
+    }
+
+    #[test]
+    fn change_password_checks_old_password() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.create_user("alice", "password123")
+            .expect("user created");
+
+        repo.change_password("alice", "password123", "n3w-secret")
+            .expect("password changed");
+        assert!(repo.verify_user("alice", "password123").unwrap().is_none());
+        assert!(repo.verify_user("alice", "n3w-secret").unwrap().is_some());
+
+        let wrong_old = repo.change_password("alice", "password123", "other");
+        assert!(matches!(wrong_old, Err(DomainError::InvalidData(_))));
+        assert!(repo.verify_user("alice", "n3w-secret").unwrap().is_some());
+
+        let unknown = repo.change_password("bob", "whatever", "other");
+        assert!(matches!(unknown, Err(DomainError::NotFound)));
+    }
+
+    #[test]
+    fn delete_user_removes_account_but_keeps_entries() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.create_user("alice", "password123")
+            .expect("user created");
+        repo.add(NewEntry {
+            kind: EntryKind::Expense,
+            amount: usd(500),
+            category: Category::new("food").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 6, 1).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+
+        let wrong = repo.delete_user("alice", "wrong");
+        assert!(matches!(wrong, Err(DomainError::InvalidData(_))));
+        let unknown = repo.delete_user("bob", "password123");
+        assert!(matches!(unknown, Err(DomainError::NotFound)));
+
+        repo.delete_user("alice", "password123")
+            .expect("user deleted");
+        let users = repo.list_users().expect("list users");
+        assert!(!users.contains(&"alice".to_string()));
+        assert_eq!(repo.list(EntryFilter::default()).unwrap().len(), 1);
+    }
+
+    /// Keeps every log record so tests can look for the ones they expect.
+    struct CapturingLogger {
+        records: std::sync::Mutex<Vec<(log::Level, String)>>,
+    }
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _metadata: &log::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record<'_>) {
+            self.records
+                .lock()
+                .expect("logger lock")
+                .push((record.level(), record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn failed_migration_is_logged() {
+        static LOGGER: CapturingLogger = CapturingLogger {
+            records: std::sync::Mutex::new(Vec::new()),
+        };
+        // Other tests may have installed it already; only one logger per process.
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut migrations = MIGRATIONS.to_vec();
+        migrations.push(("999_broken.sql", "CREATE TABLE ("));
+        let result = repo.run_migrations(&migrations);
+        assert!(matches!(result, Err(DomainError::Storage(_))));
 
-        let _ = fs::remove_file(path);
+        let records = LOGGER.records.lock().expect("logger lock");
+        assert!(records.iter().any(|(level, message)| {
+            *level == log::Level::Error && message.starts_with("migration 999_broken.sql failed")
+        }));
     }
 }
@@ -1,66 +1,833 @@
-use std::io;
-
 use ratatui::Frame;
+use ratatui::layout::Alignment;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 
+use crate::config::Config;
+use crate::error::UiError;
 use crate::event::Action;
+use crate::layout::centered_rect;
 use crate::screens::{
-    CreateUserScreen, DashboardScreen, LoginScreen, Screen, ScreenId, ScreenResult,
+    AddEntryScreen, CreateUserScreen, DashboardScreen, LoginScreen, Screen, ScreenId, ScreenResult,
+    StatsScreen, user_message,
 };
-use domain::EntryRepository;
+use crate::theme::Theme;
+use crate::toast::{Toast, ToastLevel};
+use domain::{EntryRepository, User};
 
 pub struct App {
     should_quit: bool,
     active_screen_id: ScreenId,
+    /// Screens below the active one, most recent last; `Back` returns to
+    /// the top of this stack.
+    back_stack: Vec<ScreenId>,
     dashboard: DashboardScreen,
     login: LoginScreen,
     create_user: CreateUserScreen,
+    add_entry: AddEntryScreen,
+    stats: StatsScreen,
     repo: Box<dyn EntryRepository>,
+    current_user: Option<User>,
+    show_help: bool,
+    /// First help line shown; the list scrolls when the terminal is shorter.
+    help_scroll: u16,
+    /// Ask "are you sure?" before quitting; tests turn this off.
+    confirm_quit: bool,
+    quit_pending: bool,
+    toast: Option<Toast>,
+    /// Message of a fatal error; while set, the next key ends the session.
+    fatal_error: Option<String>,
+    theme: Theme,
 }
 
+/// Smallest terminal the forms fit in; below this only a notice is shown.
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 16;
+
+const HELP_LINES: &[(&str, &str)] = &[
+    ("Ctrl+Q / Ctrl+C", "quit"),
+    ("Ctrl+L", "log out"),
+    ("Ctrl+R", "show / hide password"),
+    ("Tab / Shift+Tab", "next / previous field"),
+    ("Up / Down", "move selection"),
+    ("h j k l / g G", "vim navigation (TUI_MONEY_KEYMAP=vim)"),
+    ("Left / Right", "move cursor, switch buttons"),
+    ("Home / End", "start / end of line"),
+    ("Enter", "activate"),
+    ("Esc", "close dialog, then go back"),
+    ("r", "reload entries"),
+    ("a", "add entry"),
+    ("c", "duplicate entry, dated today"),
+    ("y", "copy entry to clipboard"),
+    ("Space", "mark entry cleared / uncleared"),
+    ("o", "show only uncleared entries"),
+    ("d", "delete entry"),
+    ("u", "undo last delete"),
+    ("/", "filter by category"),
+    ("f", "filter by dates"),
+    ("m / M", "this month / last month"),
+    ("x", "reset filters"),
+    ("s", "category stats"),
+    ("?", "toggle this help"),
+];
+
 impl App {
     pub fn new(repo: Box<dyn EntryRepository>) -> Self {
-        Self {
+        let mut app = Self {
             should_quit: false,
             active_screen_id: ScreenId::Login,
+            back_stack: Vec::new(),
             dashboard: DashboardScreen::new(),
             login: LoginScreen::new(),
             create_user: CreateUserScreen::new(),
+            add_entry: AddEntryScreen::new(),
+            stats: StatsScreen::new(),
             repo,
-        }
+            current_user: None,
+            show_help: false,
+            help_scroll: 0,
+            confirm_quit: true,
+            quit_pending: false,
+            toast: None,
+            fatal_error: None,
+            theme: Theme::default(),
+        };
+        app.open_login();
+        app
     }
 
     pub fn render(&mut self, frame: &mut Frame<'_>) {
+        let area = frame.area();
+        if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+            render_too_small(frame);
+            return;
+        }
+        let theme = &self.theme;
         match self.active_screen_id {
-            ScreenId::Dashboard => self.dashboard.render(frame),
-            ScreenId::Login => self.login.render(frame),
-            ScreenId::CreateUser => self.create_user.render(frame),
+            ScreenId::Dashboard => self.dashboard.render(frame, theme),
+            ScreenId::Login => self.login.render(frame, theme),
+            ScreenId::CreateUser => self.create_user.render(frame, theme),
+            ScreenId::AddEntry => self.add_entry.render(frame, theme),
+            ScreenId::Stats => self.stats.render(frame, theme),
+        }
+        if let Some(toast) = &mut self.toast
+            && !toast.render(frame, theme)
+        {
+            self.toast = None;
+        }
+        if self.show_help {
+            render_help(frame, &mut self.help_scroll);
+        }
+        if self.quit_pending {
+            render_confirm_quit(frame);
+        }
+        if let Some(message) = &self.fatal_error {
+            render_fatal_error(frame, message, theme);
+        }
+    }
+
+    /// Shows `message` over the bottom row for a few seconds, replacing any
+    /// toast that is still visible.
+    pub fn set_toast(&mut self, message: impl Into<String>, level: ToastLevel) {
+        self.toast = Some(Toast::new(message, level));
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// Applies the display settings from `config`; the keymap stays with the
+    /// event loop.
+    pub fn apply_config(&mut self, config: &Config) {
+        self.set_theme(config.theme);
+        self.dashboard.set_date_format(&config.date_format);
+    }
+
+    /// Lists expenses as negative and income as positive amounts, whatever
+    /// sign they are stored with.
+    pub fn set_signed_amounts(&mut self, signed: bool) {
+        self.dashboard.set_signed_amounts(signed);
+    }
+
+    /// Reports an error to the user: fatal ones in a dialog that ends the
+    /// session on the next key, recoverable ones as a toast.
+    pub fn report(&mut self, err: UiError) {
+        log::error!("{err}");
+        let message = match &err {
+            UiError::Domain(e) => user_message(e),
+            other => other.to_string(),
+        };
+        if err.is_fatal() {
+            self.fatal_error = Some(message);
+        } else {
+            self.set_toast(message, ToastLevel::Error);
         }
     }
 
-    pub fn apply(&mut self, action: Action) -> io::Result<bool> {
+    pub fn apply(&mut self, action: Action) -> Result<bool, UiError> {
+        if action == Action::Interrupt {
+            self.should_quit = true;
+            return Ok(true);
+        }
+        if self.fatal_error.is_some() {
+            if action != Action::None {
+                self.should_quit = true;
+            }
+            return Ok(self.should_quit);
+        }
+        if self.quit_pending {
+            match action {
+                Action::InputChar('y') => self.should_quit = true,
+                Action::InputChar('n') | Action::Cancel => self.quit_pending = false,
+                _ => {}
+            }
+            return Ok(self.should_quit);
+        }
+        if self.show_help {
+            match action {
+                Action::Quit => {
+                    self.show_help = false;
+                    self.request_quit();
+                }
+                Action::Help | Action::Cancel | Action::InputChar('q') => self.show_help = false,
+                Action::NavDown => self.help_scroll = self.help_scroll.saturating_add(1),
+                Action::NavUp => self.help_scroll = self.help_scroll.saturating_sub(1),
+                _ => {}
+            }
+            return Ok(self.should_quit);
+        }
+        let action = match action {
+            Action::Help if self.active_screen().captures_text() => Action::InputChar('?'),
+            Action::Help => {
+                self.show_help = true;
+                self.help_scroll = 0;
+                return Ok(self.should_quit);
+            }
+            other => other,
+        };
+
         let repo = &mut *self.repo;
         let result = match self.active_screen_id {
             ScreenId::Dashboard => self.dashboard.handle_action(action, repo),
             ScreenId::Login => self.login.handle_action(action, repo),
             ScreenId::CreateUser => self.create_user.handle_action(action, repo),
+            ScreenId::AddEntry => self.add_entry.handle_action(action, repo),
+            ScreenId::Stats => self.stats.handle_action(action, repo),
         };
 
         match result {
-            ScreenResult::Quit => self.should_quit = true,
-            ScreenResult::Go(id) => self.switch_screen(id),
+            ScreenResult::Quit => self.request_quit(),
+            ScreenResult::Go(id) => self.push(id),
+            ScreenResult::Back => self.pop(),
+            ScreenResult::BackToEntry(id) => {
+                self.dashboard.select_on_refresh(id);
+                self.pop();
+            }
+            ScreenResult::UserCreated(username) => {
+                self.push(ScreenId::Login);
+                self.login.prefill_username(&username);
+                self.set_toast(
+                    format!("Created user {username}; log in now"),
+                    ToastLevel::Success,
+                );
+            }
+            ScreenResult::LoggedIn(user) => {
+                self.set_toast(format!("Logged in as {}", user.username), ToastLevel::Info);
+                self.current_user = Some(user);
+                self.push(ScreenId::Dashboard);
+            }
+            ScreenResult::Logout => {
+                self.current_user = None;
+                self.set_toast("Logged out", ToastLevel::Info);
+                self.open_login();
+            }
+            ScreenResult::Toast(message, level) => self.set_toast(message, level),
+            ScreenResult::Error(e) => self.report(e.into()),
             ScreenResult::None => {}
         }
 
         Ok(self.should_quit)
     }
 
+    #[cfg(test)]
+    pub(crate) fn active_screen_id(&self) -> ScreenId {
+        self.active_screen_id
+    }
+
+    /// True while a text field has focus; the event layer then delivers
+    /// plain characters verbatim instead of applying keymap shortcuts.
+    pub fn captures_text(&self) -> bool {
+        !self.show_help && !self.quit_pending && self.active_screen().captures_text()
+    }
+
+    fn request_quit(&mut self) {
+        if self.confirm_quit {
+            self.quit_pending = true;
+        } else {
+            self.should_quit = true;
+        }
+    }
+
+    fn active_screen(&self) -> &dyn Screen {
+        match self.active_screen_id {
+            ScreenId::Dashboard => &self.dashboard,
+            ScreenId::Login => &self.login,
+            ScreenId::CreateUser => &self.create_user,
+            ScreenId::AddEntry => &self.add_entry,
+            ScreenId::Stats => &self.stats,
+        }
+    }
+
+    /// Opens `id` on top of the active screen. The roots (`Login` and
+    /// `Dashboard`) start a fresh stack, so `Back` never goes past them.
+    fn push(&mut self, id: ScreenId) {
+        if matches!(id, ScreenId::Login | ScreenId::Dashboard) {
+            self.back_stack.clear();
+        } else {
+            self.back_stack.push(self.active_screen_id);
+        }
+        self.switch_screen(id);
+    }
+
+    /// Shows the login screen, or the account form on top of it while no
+    /// user exists yet.
+    fn open_login(&mut self) {
+        self.push(ScreenId::Login);
+        if self.login.is_first_run() {
+            self.push(ScreenId::CreateUser);
+        }
+    }
+
+    /// Returns to the previous screen, reloading it; does nothing on a root.
+    fn pop(&mut self) {
+        if let Some(id) = self.back_stack.pop() {
+            self.switch_screen(id);
+        }
+    }
+
     fn switch_screen(&mut self, id: ScreenId) {
         self.active_screen_id = id;
         let repo = &mut *self.repo;
-        let _ = match self.active_screen_id {
+        let result = match self.active_screen_id {
             ScreenId::Dashboard => self.dashboard.init(repo),
             ScreenId::Login => self.login.init(repo),
             ScreenId::CreateUser => self.create_user.init(repo),
+            ScreenId::AddEntry => self.add_entry.init(repo),
+            ScreenId::Stats => self.stats.init(repo),
         };
+        if let Err(e) = result {
+            self.report(e.into());
+        }
+    }
+}
+
+/// Draws the key list. `scroll` is clamped here, where the visible height
+/// is known, and only matters when the list does not fit.
+fn render_help(frame: &mut Frame<'_>, scroll: &mut u16) {
+    let rows = HELP_LINES.len() as u16;
+    let area = centered_rect(frame.area(), 60, rows + 2);
+    let visible = area.height.saturating_sub(2);
+    *scroll = (*scroll).min(rows.saturating_sub(visible));
+    frame.render_widget(Clear, area);
+
+    let key_style = Style::default().add_modifier(Modifier::BOLD);
+    let lines: Vec<Line> = HELP_LINES
+        .iter()
+        .map(|(key, description)| {
+            Line::from(vec![
+                Span::styled(format!("{:<18}", key), key_style),
+                Span::raw(*description),
+            ])
+        })
+        .collect();
+    let mut block = Block::default().title("Help").borders(Borders::ALL);
+    if visible < rows {
+        block = block.title_bottom(Line::from(" Up/Down: scroll ").right_aligned());
+    }
+    let help = Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Left)
+        .scroll((*scroll, 0));
+    frame.render_widget(help, area);
+}
+
+fn render_too_small(frame: &mut Frame<'_>) {
+    let message = format!("Terminal too small (need {MIN_WIDTH}x{MIN_HEIGHT})");
+    let area = frame.area();
+    let row = centered_rect(area, area.width, 1);
+    frame.render_widget(Paragraph::new(message).alignment(Alignment::Center), row);
+}
+
+fn render_fatal_error(frame: &mut Frame<'_>, message: &str, theme: &Theme) {
+    let area = centered_rect(frame.area(), 60, 6);
+    frame.render_widget(Clear, area);
+    let dialog = Paragraph::new(vec![
+        Line::from(message.to_string()),
+        Line::default(),
+        Line::from("Press any key to quit."),
+    ])
+    .block(
+        Block::default()
+            .title("Error")
+            .borders(Borders::ALL)
+            .border_style(theme.error),
+    )
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+    frame.render_widget(dialog, area);
+}
+
+fn render_confirm_quit(frame: &mut Frame<'_>) {
+    let area = centered_rect(frame.area(), 42, 3);
+    frame.render_widget(Clear, area);
+    let prompt = Paragraph::new("Are you sure you want to quit? [y/n]")
+        .block(Block::default().title("Quit").borders(Borders::ALL))
+        .alignment(Alignment::Center);
+    frame.render_widget(prompt, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use domain::{Category, EntryFilter, EntryId, EntryKind, NewEntry, UserRepository};
+    use rusty_money::{Money, iso};
+    use storage::SqliteRepository;
+
+    fn type_text(app: &mut App, text: &str) {
+        for ch in text.chars() {
+            app.apply(Action::InputChar(ch)).expect("apply");
+        }
+    }
+
+    fn login(app: &mut App, username: &str, password: &str) {
+        type_text(app, username);
+        app.apply(Action::FocusNext).expect("apply");
+        type_text(app, password);
+        app.apply(Action::Activate).expect("apply");
+    }
+
+    #[test]
+    fn dashboard_loads_entries_after_login() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.create_user("alice", "secret-42")
+            .expect("user created");
+        repo.add(NewEntry {
+            kind: EntryKind::Expense,
+            amount: Money::from_minor(999, iso::USD),
+            category: Category::new("coffee").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 6, 1).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+        let mut app = App::new(Box::new(repo));
+        assert!(app.dashboard.entries().is_empty());
+
+        login(&mut app, "alice", "secret-42");
+        assert_eq!(app.active_screen_id(), ScreenId::Dashboard);
+        assert_eq!(app.dashboard.entries().len(), 1);
+    }
+
+    #[test]
+    fn entry_added_through_screens_is_persisted() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.create_user("alice", "secret-42")
+            .expect("user created");
+        let mut app = App::new(Box::new(repo));
+
+        login(&mut app, "alice", "secret-42");
+        assert_eq!(app.active_screen_id(), ScreenId::Dashboard);
+
+        type_text(&mut app, "a");
+        assert_eq!(app.active_screen_id(), ScreenId::AddEntry);
+        app.apply(Action::FocusNext).expect("apply");
+        type_text(&mut app, "12.50");
+        app.apply(Action::FocusNext).expect("apply");
+        type_text(&mut app, "food");
+        for _ in 0..3 {
+            app.apply(Action::FocusNext).expect("apply");
+        }
+        app.apply(Action::Activate).expect("apply");
+        assert_eq!(app.active_screen_id(), ScreenId::Dashboard);
+
+        let entries = app
+            .repo
+            .list(EntryFilter::default())
+            .expect("entries listed");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].category.as_str(), "food");
+        assert_eq!(entries[0].amount.to_string(), "$12.50");
+    }
+
+    #[test]
+    fn added_entry_is_selected_on_the_dashboard() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        // Dated in the future so they list above the new entry (dated today).
+        for day in 1..=3 {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: Money::from_minor(100, iso::USD),
+                category: Category::new("rent").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2099, 1, day).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+        let mut app = App::new(Box::new(repo));
+        app.push(ScreenId::Dashboard);
+        assert_eq!(
+            app.dashboard.selected_entry().map(|e| e.id),
+            Some(EntryId(3))
+        );
+
+        type_text(&mut app, "a");
+        app.apply(Action::FocusNext).expect("apply");
+        type_text(&mut app, "7");
+        app.apply(Action::FocusNext).expect("apply");
+        type_text(&mut app, "food");
+        for _ in 0..3 {
+            app.apply(Action::FocusNext).expect("apply");
+        }
+        app.apply(Action::Activate).expect("apply");
+
+        assert_eq!(app.active_screen_id(), ScreenId::Dashboard);
+        let selected = app.dashboard.selected_entry().expect("selection");
+        assert_eq!(selected.category.as_str(), "food");
+        assert_eq!(
+            app.dashboard.entries().last().map(|e| e.id),
+            Some(selected.id)
+        );
+    }
+
+    #[test]
+    fn first_run_opens_the_account_form() {
+        let repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut app = App::new(Box::new(repo));
+        assert_eq!(app.active_screen_id(), ScreenId::CreateUser);
+
+        // Esc goes back to the login screen it was opened over.
+        app.apply(Action::Cancel).expect("apply");
+        assert_eq!(app.active_screen_id(), ScreenId::Login);
+        app.apply(Action::FocusPrev).expect("apply");
+        app.apply(Action::Activate).expect("apply");
+        assert_eq!(app.active_screen_id(), ScreenId::CreateUser);
+
+        for (i, text) in ["alice", "secret-42", "secret-42"].into_iter().enumerate() {
+            if i > 0 {
+                app.apply(Action::FocusNext).expect("apply");
+            }
+            type_text(&mut app, text);
+        }
+        app.apply(Action::FocusNext).expect("apply");
+        app.apply(Action::Activate).expect("apply");
+        assert_eq!(app.active_screen_id(), ScreenId::Login);
+        assert_eq!(app.login.username(), "alice");
+
+        // The name is filled in; only the password is left to type.
+        type_text(&mut app, "secret-42");
+        app.apply(Action::Activate).expect("apply");
+        assert_eq!(app.active_screen_id(), ScreenId::Dashboard);
+
+        // Coming back later, the form no longer holds the old account.
+        app.apply(Action::Logout).expect("apply");
+        app.apply(Action::FocusPrev).expect("apply");
+        app.apply(Action::Activate).expect("apply");
+        assert_eq!(app.active_screen_id(), ScreenId::CreateUser);
+        assert!(app.create_user.is_blank());
+    }
+
+    #[test]
+    fn help_scrolls_to_its_last_line_on_a_small_terminal() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.create_user("alice", "secret-42")
+            .expect("user created");
+        let mut app = App::new(Box::new(repo));
+        login(&mut app, "alice", "secret-42");
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).expect("terminal");
+        let mut screen_text = |app: &mut App| {
+            terminal.draw(|frame| app.render(frame)).expect("draw");
+            let buffer = terminal.backend().buffer();
+            (0..buffer.area.height)
+                .flat_map(|y| (0..buffer.area.width).map(move |x| (x, y)))
+                .map(|pos| buffer[pos].symbol())
+                .collect::<String>()
+        };
+
+        app.apply(Action::Help).expect("apply");
+        let text = screen_text(&mut app);
+        assert!(text.contains("Up/Down: scroll"));
+        assert!(!text.contains("toggle this help"));
+
+        for _ in 0..HELP_LINES.len() {
+            app.apply(Action::NavDown).expect("apply");
+        }
+        let text = screen_text(&mut app);
+        assert!(text.contains("toggle this help"));
+        assert_eq!(app.help_scroll, 2);
+
+        app.apply(Action::Help).expect("apply");
+        app.apply(Action::Help).expect("apply");
+        assert_eq!(app.help_scroll, 0);
+    }
+
+    #[test]
+    fn help_overlay_toggles_without_touching_screen() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.create_user("alice", "secret-42")
+            .expect("user created");
+        let mut app = App::new(Box::new(repo));
+
+        // On the login form `?` is just a character for the username.
+        app.apply(Action::Help).expect("apply");
+        assert!(!app.show_help);
+        assert_eq!(app.login.username(), "?");
+
+        app.apply(Action::FocusNext).expect("apply");
+        app.apply(Action::FocusNext).expect("apply");
+        app.apply(Action::Help).expect("apply");
+        assert!(app.show_help);
+        app.apply(Action::Activate).expect("apply");
+        assert_eq!(app.active_screen_id(), ScreenId::Login);
+        app.apply(Action::InputChar('q')).expect("apply");
+        assert!(!app.show_help);
+
+        app.apply(Action::Help).expect("apply");
+        app.apply(Action::Cancel).expect("apply");
+        assert!(!app.show_help);
+    }
+
+    #[test]
+    fn toast_is_drawn_then_expires() {
+        use crate::toast::TOAST_FRAMES;
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut app = App::new(Box::new(repo));
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).expect("terminal");
+        let bottom_row = |terminal: &Terminal<TestBackend>| {
+            let buffer = terminal.backend().buffer();
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, buffer.area.height - 1)].symbol())
+                .collect::<String>()
+        };
+
+        app.set_toast("Saved", ToastLevel::Success);
+        terminal.draw(|frame| app.render(frame)).expect("draw");
+        assert!(bottom_row(&terminal).contains("Saved"));
+
+        for _ in 0..TOAST_FRAMES {
+            terminal.draw(|frame| app.render(frame)).expect("draw");
+        }
+        assert!(app.toast.is_none());
+        assert!(!bottom_row(&terminal).contains("Saved"));
+    }
+
+    #[test]
+    fn tiny_terminals_render_without_panicking() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut app = App::new(Box::new(repo));
+        let screens = [
+            ScreenId::Login,
+            ScreenId::CreateUser,
+            ScreenId::Dashboard,
+            ScreenId::AddEntry,
+            ScreenId::Stats,
+        ];
+        for (width, height) in [(5, 5), (80, 1), (1, 40)] {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).expect("terminal");
+            for id in screens {
+                app.switch_screen(id);
+                app.show_help = true;
+                terminal.draw(|frame| app.render(frame)).expect("draw");
+                // Screens must also cope on their own, below the app's guard.
+                terminal
+                    .draw(|frame| match id {
+                        ScreenId::Login => app.login.render(frame, &app.theme),
+                        ScreenId::CreateUser => app.create_user.render(frame, &app.theme),
+                        ScreenId::Dashboard => app.dashboard.render(frame, &app.theme),
+                        ScreenId::AddEntry => app.add_entry.render(frame, &app.theme),
+                        ScreenId::Stats => app.stats.render(frame, &app.theme),
+                    })
+                    .expect("draw");
+            }
+        }
+
+        let mut terminal = Terminal::new(TestBackend::new(5, 5)).expect("terminal");
+        terminal.draw(|frame| app.render(frame)).expect("draw");
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(0, 2)].symbol(), "T");
+    }
+
+    #[test]
+    fn repository_errors_are_shown() {
+        use domain::{DomainError, MockRepository};
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let screen_text = |app: &mut App| {
+            let mut terminal = Terminal::new(TestBackend::new(80, 24)).expect("terminal");
+            terminal.draw(|frame| app.render(frame)).expect("draw");
+            let buffer = terminal.backend().buffer().clone();
+            (0..buffer.area.height)
+                .flat_map(|y| (0..buffer.area.width).map(move |x| (x, y)))
+                .map(|pos| buffer[pos].symbol().to_string())
+                .collect::<String>()
+        };
+
+        let mut repo = MockRepository::new();
+        repo.create_user("alice", "secret-42")
+            .expect("user created");
+        repo.fail_on("list", DomainError::InvalidData("bad filter".to_string()));
+        let mut app = App::new(Box::new(repo));
+        login(&mut app, "alice", "secret-42");
+        assert_eq!(app.active_screen_id(), ScreenId::Dashboard);
+        assert!(screen_text(&mut app).contains("bad filter"));
+        assert!(app.fatal_error.is_none());
+
+        // Storage failures are toasts too; reloading tries again.
+        let repo = MockRepository::new();
+        repo.fail_on(
+            "list",
+            DomainError::Storage("database is locked".to_string()),
+        );
+        let mut app = App::new(Box::new(repo));
+        app.switch_screen(ScreenId::Dashboard);
+        assert!(screen_text(&mut app).contains("Something went wrong saving your data."));
+        assert!(app.fatal_error.is_none());
+        assert!(!app.apply(Action::InputChar('r')).expect("apply"));
+        assert_eq!(app.active_screen_id(), ScreenId::Dashboard);
+
+        app.report(UiError::Io(std::io::Error::other("terminal gone")));
+        assert!(app.fatal_error.is_some());
+        assert!(app.apply(Action::InputChar('r')).expect("apply"));
+    }
+
+    #[test]
+    fn signed_amounts_show_expenses_with_a_minus() {
+        use domain::{Category, NewEntryBuilder};
+        use ratatui::{Terminal, backend::TestBackend};
+        use rusty_money::{Money, iso};
+
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.add(
+            NewEntryBuilder::new()
+                .amount(Money::from_minor(1250, iso::USD))
+                .category(Category::new("food").unwrap())
+                .build()
+                .expect("entry built"),
+        )
+        .expect("entry added");
+        let mut app = App::new(Box::new(repo));
+        app.switch_screen(ScreenId::Dashboard);
+        let food_row = |app: &mut App| {
+            let mut terminal = Terminal::new(TestBackend::new(80, 24)).expect("terminal");
+            terminal.draw(|frame| app.render(frame)).expect("draw");
+            let buffer = terminal.backend().buffer().clone();
+            (0..buffer.area.height)
+                .map(|y| {
+                    (0..buffer.area.width)
+                        .map(|x| buffer[(x, y)].symbol())
+                        .collect::<String>()
+                })
+                .find(|row| row.contains("food"))
+                .expect("entry rendered")
+        };
+
+        let amount = |row: String| row.split("food").nth(1).unwrap_or("").trim().to_string();
+        assert!(amount(food_row(&mut app)).starts_with("$12.50"));
+        app.set_signed_amounts(true);
+        assert!(amount(food_row(&mut app)).starts_with("-$12.50"));
+    }
+
+    #[test]
+    fn back_returns_through_the_screen_stack() {
+        let repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut app = App::new(Box::new(repo));
+        app.push(ScreenId::Dashboard);
+        app.apply(Action::InputChar('s')).expect("apply");
+        assert_eq!(app.active_screen_id(), ScreenId::Stats);
+        app.apply(Action::Help).expect("apply");
+        assert!(app.show_help);
+
+        // Esc closes help first, then walks back down the stack.
+        app.apply(Action::Cancel).expect("apply");
+        assert!(!app.show_help);
+        assert_eq!(app.active_screen_id(), ScreenId::Stats);
+        app.apply(Action::Cancel).expect("apply");
+        assert_eq!(app.active_screen_id(), ScreenId::Dashboard);
+        assert!(app.back_stack.is_empty());
+
+        // The dashboard is the root: nothing to pop.
+        app.pop();
+        assert_eq!(app.active_screen_id(), ScreenId::Dashboard);
+
+        app.push(ScreenId::AddEntry);
+        app.push(ScreenId::Stats);
+        assert_eq!(app.back_stack, [ScreenId::Dashboard, ScreenId::AddEntry]);
+        app.pop();
+        assert_eq!(app.active_screen_id(), ScreenId::AddEntry);
+        app.push(ScreenId::Dashboard);
+        assert!(app.back_stack.is_empty());
+    }
+
+    #[test]
+    fn quit_asks_for_confirmation() {
+        let repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut app = App::new(Box::new(repo));
+
+        assert!(!app.apply(Action::Quit).expect("apply"));
+        assert!(app.quit_pending);
+        assert!(!app.apply(Action::InputChar('n')).expect("apply"));
+        assert!(!app.quit_pending);
+
+        app.apply(Action::Quit).expect("apply");
+        assert!(!app.apply(Action::Cancel).expect("apply"));
+        app.apply(Action::Quit).expect("apply");
+        assert!(app.apply(Action::InputChar('y')).expect("apply"));
+    }
+
+    #[test]
+    fn quit_without_confirmation_and_interrupt() {
+        let repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut app = App::new(Box::new(repo));
+        assert!(app.apply(Action::Interrupt).expect("apply"));
+
+        let repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut app = App::new(Box::new(repo));
+        app.confirm_quit = false;
+        assert!(app.apply(Action::Quit).expect("apply"));
+    }
+
+    #[test]
+    fn logout_clears_user_and_resets_login() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.create_user("alice", "secret-42")
+            .expect("user created");
+        let mut app = App::new(Box::new(repo));
+
+        login(&mut app, "alice", "secret-42");
+        assert_eq!(app.active_screen_id(), ScreenId::Dashboard);
+        assert_eq!(
+            app.current_user.as_ref().map(|u| u.username.as_str()),
+            Some("alice")
+        );
+
+        app.apply(Action::Logout).expect("apply");
+        assert_eq!(app.active_screen_id(), ScreenId::Login);
+        assert!(app.current_user.is_none());
+
+        // The login form starts empty again.
+        app.apply(Action::FocusNext).expect("apply");
+        type_text(&mut app, "secret-42");
+        app.apply(Action::Activate).expect("apply");
+        assert_eq!(app.active_screen_id(), ScreenId::Login);
     }
 }
@@ -0,0 +1,26 @@
+/// The system clipboard, opened on first use. The handle is kept for the
+/// rest of the session because on X11 the copied text is only served while
+/// it is alive.
+#[derive(Default)]
+pub struct Clipboard {
+    #[cfg(feature = "clipboard")]
+    inner: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    #[cfg(feature = "clipboard")]
+    pub fn copy(&mut self, text: &str) -> Result<(), String> {
+        let inner = match &mut self.inner {
+            Some(inner) => inner,
+            None => self
+                .inner
+                .insert(arboard::Clipboard::new().map_err(|err| err.to_string())?),
+        };
+        inner.set_text(text).map_err(|err| err.to_string())
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    pub fn copy(&mut self, _text: &str) -> Result<(), String> {
+        Err("built without the `clipboard` feature".to_string())
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt::Write;
+use std::io;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use domain::{DEFAULT_CURRENCY, resolve_currency};
+use rusty_money::iso;
+
+use crate::event::Keymap;
+use crate::theme::Theme;
+
+/// Defaults read from `config.toml`, e.g.
+///
+/// ```toml
+/// theme = "monochrome"
+/// keymap = "vim"
+/// date_format = "%d.%m.%Y"
+/// currency = "EUR"
+/// ```
+///
+/// Every key is optional. The `TUI_MONEY_*` environment variables still take
+/// precedence over the file.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub(crate) theme: Theme,
+    pub(crate) keymap: Keymap,
+    /// `strftime` pattern for dates in the entry list; typed dates stay ISO.
+    pub(crate) date_format: String,
+    pub currency: &'static iso::Currency,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            keymap: Keymap::default(),
+            date_format: "%Y-%m-%d".to_string(),
+            currency: DEFAULT_CURRENCY,
+        }
+    }
+}
+
+impl Config {
+    /// Reads the file at `path`; a missing file means all defaults. Returns
+    /// the config together with warnings about keys it did not recognise.
+    pub fn load(path: &Path) -> Result<(Self, Vec<String>), String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::from_toml(&text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok((Self::default(), Vec::new())),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    /// Parses config text. Unknown keys become warnings; a known key with a
+    /// bad value is an error, so a typo is reported rather than ignored.
+    pub fn from_toml(text: &str) -> Result<(Self, Vec<String>), String> {
+        let table: toml::Table = text
+            .parse()
+            .map_err(|err: toml::de::Error| format!("not valid TOML: {}", err.message()))?;
+        let mut config = Self::default();
+        let mut warnings = Vec::new();
+        for (key, value) in &table {
+            let value = match value.as_str() {
+                Some(value) => value,
+                None if is_known(key) => return Err(format!("{key} must be a string")),
+                None => "",
+            };
+            match key.as_str() {
+                "theme" => {
+                    config.theme = Theme::from_name(value).ok_or(format!(
+                        "unknown theme: {value} (expected default or monochrome)"
+                    ))?;
+                }
+                "keymap" => {
+                    config.keymap = Keymap::from_name(value)
+                        .ok_or(format!("unknown keymap: {value} (expected default or vim)"))?;
+                }
+                "date_format" => {
+                    validate_date_format(value)?;
+                    config.date_format = value.to_string();
+                }
+                "currency" => {
+                    config.currency =
+                        resolve_currency(Some(value)).map_err(|err| format!("currency: {err}"))?;
+                }
+                _ => warnings.push(format!("unknown config key: {key}")),
+            }
+        }
+        Ok((config, warnings))
+    }
+}
+
+fn is_known(key: &str) -> bool {
+    matches!(key, "theme" | "keymap" | "date_format" | "currency")
+}
+
+/// Formats a sample date, which fails both for unknown specifiers and for
+/// ones a `NaiveDate` cannot fill (like `%H`); the dashboard would otherwise
+/// panic on the first render.
+fn validate_date_format(format: &str) -> Result<(), String> {
+    let mut sample = String::new();
+    if format.is_empty() || write!(sample, "{}", NaiveDate::MIN.format(format)).is_err() {
+        return Err(format!("invalid date_format: {format}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_full_config() {
+        let (config, warnings) = Config::from_toml(
+            r#"
+            theme = "monochrome"
+            keymap = "vim"
+            date_format = "%d.%m.%Y"
+            currency = "eur"
+            "#,
+        )
+        .expect("parsed");
+        assert!(warnings.is_empty());
+        assert_eq!(config.theme, Theme::monochrome());
+        assert_eq!(config.date_format, "%d.%m.%Y");
+        assert_eq!(config.currency, iso::EUR);
+    }
+
+    #[test]
+    fn empty_config_uses_defaults() {
+        let (config, warnings) = Config::from_toml("").expect("parsed");
+        assert!(warnings.is_empty());
+        assert_eq!(config.theme, Theme::default());
+        assert_eq!(config.date_format, "%Y-%m-%d");
+        assert_eq!(config.currency, DEFAULT_CURRENCY);
+
+        let missing = Path::new("/nonexistent/tui-money/config.toml");
+        let (config, _) = Config::load(missing).expect("defaults");
+        assert_eq!(config.currency, DEFAULT_CURRENCY);
+    }
+
+    #[test]
+    fn unknown_keys_warn_and_bad_values_fail() {
+        let (config, warnings) =
+            Config::from_toml("theme = \"default\"\ncolour = \"red\"").expect("parsed");
+        assert_eq!(warnings, ["unknown config key: colour"]);
+        assert_eq!(config.theme, Theme::default());
+
+        assert_eq!(
+            Config::from_toml("theme = \"neon\"").unwrap_err(),
+            "unknown theme: neon (expected default or monochrome)"
+        );
+        assert_eq!(
+            Config::from_toml("currency = 42").unwrap_err(),
+            "currency must be a string"
+        );
+        assert!(Config::from_toml("date_format = \"%Q\"").is_err());
+        assert_eq!(
+            Config::from_toml("date_format = \"%H:%M\"").unwrap_err(),
+            "invalid date_format: %H:%M"
+        );
+        assert!(Config::from_toml("theme = ").is_err());
+    }
+}
@@ -0,0 +1,23 @@
+use std::io;
+
+use domain::DomainError;
+use thiserror::Error;
+
+/// Everything that can go wrong while the TUI runs.
+#[derive(Debug, Error)]
+pub enum UiError {
+    /// The terminal itself failed; nothing more can be drawn.
+    #[error("terminal error: {0}")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Domain(#[from] DomainError),
+}
+
+impl UiError {
+    /// Fatal errors are shown in a dialog that ends the session; the rest are
+    /// reported as toasts and the user can try again. A database that cannot
+    /// be opened never gets this far: `main` reports it before the TUI starts.
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, UiError::Io(_))
+    }
+}
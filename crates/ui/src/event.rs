@@ -1,38 +1,223 @@
-use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use std::collections::HashMap;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Action {
     None,
     Quit,
+    /// Ctrl+C: quit immediately, skipping any confirmation.
+    Interrupt,
     FocusNext,
     FocusPrev,
     Activate,
     Cancel,
     InputChar(char),
+    /// Text pasted in one go (bracketed paste); inserted at the cursor.
+    Paste(String),
     Backspace,
     NavUp,
     NavDown,
     NavLeft,
     NavRight,
+    /// Jump to the first / last row of a list.
+    NavFirst,
+    NavLast,
+    LineStart,
+    LineEnd,
+    Logout,
+    Help,
+    /// Show or hide the characters of a focused password field.
+    ToggleReveal,
 }
 
-pub fn handle_event(event: &Event) -> Action {
-    match event {
-        Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
-            KeyCode::Char('q') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
-            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
-            KeyCode::Esc => Action::Cancel,
-            KeyCode::Tab => Action::FocusNext,
-            KeyCode::BackTab => Action::FocusPrev,
-            KeyCode::Up => Action::NavUp,
-            KeyCode::Down => Action::NavDown,
-            KeyCode::Left => Action::NavLeft,
-            KeyCode::Right => Action::NavRight,
-            KeyCode::Backspace => Action::Backspace,
-            KeyCode::Enter => Action::Activate,
-            KeyCode::Char(ch) if key.modifiers.is_empty() => Action::InputChar(ch),
+/// Maps key presses to actions. Unbound printable characters fall through
+/// to `Action::InputChar`.
+///
+/// Plain-character bindings (like vim's `j`) would make those letters
+/// impossible to type, so they are skipped while the active screen reports
+/// `Screen::captures_text`; `run()` passes that flag as `input_mode`.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<(KeyCode, KeyModifiers), Action>,
+}
+
+impl Keymap {
+    pub fn empty() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+
+    pub fn bind(&mut self, code: KeyCode, modifiers: KeyModifiers, action: Action) -> &mut Self {
+        self.bindings.insert((code, modifiers), action);
+        self
+    }
+
+    /// Default bindings plus `h/j/k/l` for arrows and `g`/`G` for first/last.
+    pub fn vim() -> Self {
+        let none = KeyModifiers::NONE;
+        let mut keymap = Self::default();
+        keymap
+            .bind(KeyCode::Char('h'), none, Action::NavLeft)
+            .bind(KeyCode::Char('j'), none, Action::NavDown)
+            .bind(KeyCode::Char('k'), none, Action::NavUp)
+            .bind(KeyCode::Char('l'), none, Action::NavRight)
+            .bind(KeyCode::Char('g'), none, Action::NavFirst)
+            .bind(KeyCode::Char('G'), none, Action::NavLast);
+        keymap
+    }
+
+    /// Looks up a keymap by name (`"default"` or `"vim"`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "default" => Some(Self::default()),
+            "vim" => Some(Self::vim()),
+            _ => None,
+        }
+    }
+
+    pub fn action_for(&self, key: &KeyEvent, input_mode: bool) -> Action {
+        // Shift is already reflected in the character itself ('?', 'G'), and
+        // terminals disagree on whether they report it, so it is ignored.
+        let modifiers = key.modifiers - KeyModifiers::SHIFT;
+        if let KeyCode::Char(ch) = key.code
+            && input_mode
+            && modifiers.is_empty()
+        {
+            return Action::InputChar(ch);
+        }
+        if let Some(action) = self.bindings.get(&(key.code, modifiers)) {
+            return action.clone();
+        }
+        match key.code {
+            KeyCode::Char(ch) if modifiers.is_empty() => Action::InputChar(ch),
             _ => Action::None,
-        },
+        }
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let none = KeyModifiers::NONE;
+        let ctrl = KeyModifiers::CONTROL;
+        let mut keymap = Self::empty();
+        keymap
+            .bind(KeyCode::Char('q'), ctrl, Action::Quit)
+            .bind(KeyCode::Char('c'), ctrl, Action::Interrupt)
+            .bind(KeyCode::Char('l'), ctrl, Action::Logout)
+            .bind(KeyCode::Char('r'), ctrl, Action::ToggleReveal)
+            .bind(KeyCode::Esc, none, Action::Cancel)
+            .bind(KeyCode::Tab, none, Action::FocusNext)
+            .bind(KeyCode::BackTab, none, Action::FocusPrev)
+            .bind(KeyCode::Up, none, Action::NavUp)
+            .bind(KeyCode::Down, none, Action::NavDown)
+            .bind(KeyCode::Left, none, Action::NavLeft)
+            .bind(KeyCode::Right, none, Action::NavRight)
+            .bind(KeyCode::Home, none, Action::LineStart)
+            .bind(KeyCode::End, none, Action::LineEnd)
+            .bind(KeyCode::Backspace, none, Action::Backspace)
+            .bind(KeyCode::Enter, none, Action::Activate)
+            .bind(KeyCode::Char('?'), none, Action::Help);
+        keymap
+    }
+}
+
+pub fn handle_event(event: &Event, keymap: &Keymap, input_mode: bool) -> Action {
+    match event {
+        Event::Key(key) if key.kind == KeyEventKind::Press => keymap.action_for(key, input_mode),
+        // The next draw picks up the new size; `App::render` copes with
+        // terminals that are too small.
+        Event::Resize(..) => Action::None,
+        Event::Paste(text) => Action::Paste(text.clone()),
         _ => Action::None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> Event {
+        Event::Key(KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn default_keymap_matches_builtin_bindings() {
+        let keymap = Keymap::default();
+        let ctrl_q = press(KeyCode::Char('q'), KeyModifiers::CONTROL);
+        assert_eq!(handle_event(&ctrl_q, &keymap, false), Action::Quit);
+        let back_tab = press(KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert_eq!(handle_event(&back_tab, &keymap, false), Action::FocusPrev);
+        let question = press(KeyCode::Char('?'), KeyModifiers::SHIFT);
+        assert_eq!(handle_event(&question, &keymap, false), Action::Help);
+        let letter = press(KeyCode::Char('a'), KeyModifiers::NONE);
+        assert_eq!(
+            handle_event(&letter, &keymap, false),
+            Action::InputChar('a')
+        );
+        let ctrl_r = press(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(handle_event(&ctrl_r, &keymap, true), Action::ToggleReveal);
+        let ctrl_x = press(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert_eq!(handle_event(&ctrl_x, &keymap, false), Action::None);
+    }
+
+    #[test]
+    fn paste_events_become_paste_actions() {
+        let paste = Event::Paste("weekly shop".to_string());
+        assert_eq!(
+            handle_event(&paste, &Keymap::default(), true),
+            Action::Paste("weekly shop".to_string())
+        );
+    }
+
+    #[test]
+    fn vim_keys_navigate_only_outside_inputs() {
+        let keymap = Keymap::vim();
+        let j = press(KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(handle_event(&j, &keymap, false), Action::NavDown);
+        assert_eq!(handle_event(&j, &keymap, true), Action::InputChar('j'));
+
+        let shift_g = press(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(handle_event(&shift_g, &keymap, false), Action::NavLast);
+        assert_eq!(
+            handle_event(&shift_g, &keymap, true),
+            Action::InputChar('G')
+        );
+
+        // Non-character keys and Ctrl chords keep working while typing.
+        let ctrl_q = press(KeyCode::Char('q'), KeyModifiers::CONTROL);
+        assert_eq!(handle_event(&ctrl_q, &keymap, true), Action::Quit);
+        let down = press(KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(handle_event(&down, &keymap, true), Action::NavDown);
+    }
+
+    #[test]
+    fn keymaps_are_selected_by_name() {
+        let k = press(KeyCode::Char('k'), KeyModifiers::NONE);
+        let vim = Keymap::from_name("Vim").expect("vim keymap");
+        assert_eq!(handle_event(&k, &vim, false), Action::NavUp);
+        let default = Keymap::from_name("default").expect("default keymap");
+        assert_eq!(handle_event(&k, &default, false), Action::InputChar('k'));
+        assert!(Keymap::from_name("emacs").is_none());
+    }
+
+    #[test]
+    fn remapped_key_produces_bound_action() {
+        let mut keymap = Keymap::default();
+        keymap.bind(KeyCode::F(10), KeyModifiers::NONE, Action::Quit);
+        keymap.bind(
+            KeyCode::Char('?'),
+            KeyModifiers::NONE,
+            Action::InputChar('?'),
+        );
+
+        let f10 = press(KeyCode::F(10), KeyModifiers::NONE);
+        assert_eq!(handle_event(&f10, &keymap, false), Action::Quit);
+        let question = press(KeyCode::Char('?'), KeyModifiers::NONE);
+        assert_eq!(
+            handle_event(&question, &keymap, false),
+            Action::InputChar('?')
+        );
+    }
+}
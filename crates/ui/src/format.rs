@@ -0,0 +1,127 @@
+use domain::{Entry, EntryKind};
+use rusty_money::{FormattableCurrency, Formatter, LocalFormat, Money, Params, Position, iso};
+
+/// Formats an amount the same way everywhere on screen: grouped according to
+/// the currency's locale, with its symbol and a leading minus sign.
+pub fn format_amount(money: &Money<'static, iso::Currency>) -> String {
+    let currency = money.currency();
+    let format = LocalFormat::from_locale(currency.locale());
+    let positions = if currency.symbol_first() {
+        vec![Position::Sign, Position::Symbol, Position::Amount]
+    } else {
+        vec![
+            Position::Sign,
+            Position::Amount,
+            Position::Space,
+            Position::Symbol,
+        ]
+    };
+    let params = Params {
+        digit_separator: format.digit_separator,
+        exponent_separator: format.exponent_separator,
+        separator_pattern: format.digit_separator_pattern(),
+        positions,
+        rounding: Some(currency.exponent()),
+        symbol: Some(currency.symbol()),
+        code: Some(currency.code()),
+    };
+    Formatter::money(money, params)
+}
+
+/// Signs `amount` by its kind, for users who think of expenses as negative:
+/// expenses come out negative and income positive whatever the stored sign.
+/// Transfers are left as stored.
+pub fn signed_amount(
+    kind: EntryKind,
+    amount: Money<'static, iso::Currency>,
+) -> Money<'static, iso::Currency> {
+    let magnitude = amount.amount().abs();
+    match kind {
+        EntryKind::Expense => Money::from_decimal(-magnitude, amount.currency()),
+        EntryKind::Income => Money::from_decimal(magnitude, amount.currency()),
+        EntryKind::Transfer => amount,
+    }
+}
+
+/// One line describing `entry` for pasting elsewhere, e.g.
+/// `2024-03-02  groceries  -$42.50  weekly shop`. Expenses are signed so
+/// the direction survives without the kind; a multi-line note is joined.
+pub fn entry_summary(entry: &Entry) -> String {
+    let mut summary = format!(
+        "{}  {}  {}",
+        entry.occurred_on.format("%Y-%m-%d"),
+        entry.category.as_str(),
+        format_amount(&signed_amount(entry.kind, entry.amount))
+    );
+    let note = entry
+        .note
+        .as_deref()
+        .unwrap_or("")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if !note.is_empty() {
+        summary.push_str("  ");
+        summary.push_str(&note);
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use domain::{Category, EntryId};
+
+    #[test]
+    fn groups_thousands() {
+        assert_eq!(
+            format_amount(&Money::from_minor(123456789, iso::USD)),
+            "$1,234,567.89"
+        );
+        assert_eq!(
+            format_amount(&Money::from_minor(-250000, iso::USD)),
+            "-$2,500.00"
+        );
+    }
+
+    #[test]
+    fn signs_amounts_by_kind() {
+        let usd = |cents| Money::from_minor(cents, iso::USD);
+        assert_eq!(signed_amount(EntryKind::Expense, usd(1250)), usd(-1250));
+        assert_eq!(signed_amount(EntryKind::Expense, usd(-1250)), usd(-1250));
+        assert_eq!(signed_amount(EntryKind::Income, usd(-900)), usd(900));
+        assert_eq!(signed_amount(EntryKind::Transfer, usd(-300)), usd(-300));
+    }
+
+    #[test]
+    fn summarizes_an_entry_on_one_line() {
+        let mut entry = Entry {
+            id: EntryId(1),
+            kind: EntryKind::Expense,
+            amount: Money::from_minor(4250, iso::USD),
+            category: Category::new("groceries").unwrap(),
+            note: Some("weekly shop\r\nmilk, eggs\n".to_string()),
+            occurred_on: NaiveDate::from_ymd_opt(2024, 3, 2).expect("date"),
+            account: None,
+            tags: Vec::new(),
+            cleared: false,
+            version: 1,
+        };
+        assert_eq!(
+            entry_summary(&entry),
+            "2024-03-02  groceries  -$42.50  weekly shop milk, eggs"
+        );
+
+        entry.kind = EntryKind::Income;
+        entry.note = None;
+        assert_eq!(entry_summary(&entry), "2024-03-02  groceries  $42.50");
+    }
+
+    #[test]
+    fn formats_zero_with_decimals() {
+        assert_eq!(format_amount(&Money::from_minor(0, iso::USD)), "$0.00");
+    }
+}
@@ -6,7 +6,7 @@ pub fn main_chunks(area: Rect) -> Vec<Rect> {
         .constraints([
             Constraint::Length(3),
             Constraint::Min(0),
-            Constraint::Length(2),
+            Constraint::Length(3),
         ])
         .split(area)
         .to_vec()
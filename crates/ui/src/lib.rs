@@ -1,7 +1,13 @@
 mod app;
+mod clipboard;
+mod config;
+mod error;
 mod event;
+mod format;
 mod layout;
 mod screens;
+mod theme;
+mod toast;
 mod widgets;
 
 use std::io::{self, stdout};
@@ -9,7 +15,7 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
-use crossterm::event as ct_event;
+use crossterm::event::{self as ct_event, DisableBracketedPaste, EnableBracketedPaste};
 use crossterm::execute;
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
@@ -18,27 +24,52 @@ use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 
 use crate::app::App;
-use crate::event::handle_event;
+use crate::event::{Keymap, handle_event};
+use crate::theme::Theme;
 
 struct TerminalGuard;
 
 impl Drop for TerminalGuard {
     fn drop(&mut self) {
         let _ = disable_raw_mode();
-        let _ = execute!(stdout(), LeaveAlternateScreen);
+        let _ = execute!(stdout(), DisableBracketedPaste, LeaveAlternateScreen);
     }
 }
 
 use domain::EntryRepository;
 
-pub fn run(repo: Box<dyn EntryRepository>) -> io::Result<()> {
+pub use crate::config::Config;
+pub use crate::error::UiError;
+
+/// Runs the TUI until the user quits. Repository errors are shown inside the
+/// app; only terminal failures end up here, after the terminal is restored.
+/// `TUI_MONEY_THEME` and `TUI_MONEY_KEYMAP` override the matching settings
+/// in `config`.
+pub fn run(repo: Box<dyn EntryRepository>, mut config: Config) -> Result<(), UiError> {
     enable_raw_mode()?;
-    execute!(stdout(), EnterAlternateScreen)?;
+    execute!(stdout(), EnterAlternateScreen, EnableBracketedPaste)?;
     let _guard = TerminalGuard;
 
     let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
+    if let Some(theme) = std::env::var("TUI_MONEY_THEME")
+        .ok()
+        .and_then(|name| Theme::from_name(&name))
+    {
+        config.theme = theme;
+    }
+    if let Some(keymap) = std::env::var("TUI_MONEY_KEYMAP")
+        .ok()
+        .and_then(|name| Keymap::from_name(&name))
+    {
+        config.keymap = keymap;
+    }
     let mut app = App::new(repo);
+    app.apply_config(&config);
+    app.set_signed_amounts(
+        std::env::var("TUI_MONEY_SIGNED_AMOUNTS").is_ok_and(|value| value == "1"),
+    );
+    let keymap = config.keymap;
     let should_quit = Arc::new(AtomicBool::new(false));
     let should_quit_handle = Arc::clone(&should_quit);
 
@@ -56,7 +87,7 @@ pub fn run(repo: Box<dyn EntryRepository>) -> io::Result<()> {
 
         if ct_event::poll(Duration::from_millis(100))? {
             let evt = ct_event::read()?;
-            let action = handle_event(&evt);
+            let action = handle_event(&evt, &keymap, app.captures_text());
             if app.apply(action)? {
                 break;
             }
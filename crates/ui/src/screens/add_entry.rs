@@ -0,0 +1,627 @@
+use chrono::Local;
+use domain::{
+    Category, DEFAULT_CURRENCY, DomainError, EntryKind, EntryRepository, NewEntry, NewEntryBuilder,
+    evaluate_amount, parse_amount, parse_relative_date,
+};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use rusty_money::{Money, iso};
+
+use super::{Screen, ScreenResult, user_message};
+use crate::event::Action;
+use crate::layout::centered_rect;
+use crate::theme::Theme;
+use crate::widgets::TextInput;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const NOTE_HEIGHT: u16 = 4;
+/// Most category suggestions shown below the field at once.
+const MAX_SUGGESTIONS: usize = 4;
+
+pub struct AddEntryScreen {
+    focus: AddEntryFocus,
+    kind: EntryKind,
+    amount_input: TextInput,
+    category_input: TextInput,
+    date_input: TextInput,
+    note_input: TextInput,
+    /// Existing categories, offered as suggestions while typing one.
+    categories: Vec<Category>,
+    suggestion: usize,
+    currency: &'static iso::Currency,
+    error_message: Option<String>,
+}
+
+impl AddEntryScreen {
+    pub fn new() -> Self {
+        Self {
+            focus: AddEntryFocus::Kind,
+            kind: EntryKind::Expense,
+            amount_input: TextInput::new(),
+            category_input: TextInput::new(),
+            date_input: TextInput::new(),
+            note_input: TextInput::multiline(),
+            categories: Vec::new(),
+            suggestion: 0,
+            currency: DEFAULT_CURRENCY,
+            error_message: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+        self.date_input
+            .set_value(Local::now().date_naive().format(DATE_FORMAT).to_string());
+    }
+
+    fn focus_next(&mut self) {
+        self.focus = match self.focus {
+            AddEntryFocus::Kind => AddEntryFocus::Amount,
+            AddEntryFocus::Amount => AddEntryFocus::Category,
+            AddEntryFocus::Category => AddEntryFocus::Date,
+            AddEntryFocus::Date => AddEntryFocus::Note,
+            AddEntryFocus::Note => AddEntryFocus::SaveButton,
+            AddEntryFocus::SaveButton => AddEntryFocus::CancelButton,
+            AddEntryFocus::CancelButton => AddEntryFocus::Kind,
+        };
+    }
+
+    fn focus_prev(&mut self) {
+        self.focus = match self.focus {
+            AddEntryFocus::Kind => AddEntryFocus::CancelButton,
+            AddEntryFocus::Amount => AddEntryFocus::Kind,
+            AddEntryFocus::Category => AddEntryFocus::Amount,
+            AddEntryFocus::Date => AddEntryFocus::Category,
+            AddEntryFocus::Note => AddEntryFocus::Date,
+            AddEntryFocus::SaveButton => AddEntryFocus::Note,
+            AddEntryFocus::CancelButton => AddEntryFocus::SaveButton,
+        };
+    }
+
+    fn toggle_kind(&mut self) {
+        self.kind = match self.kind {
+            EntryKind::Expense => EntryKind::Income,
+            EntryKind::Income => EntryKind::Transfer,
+            EntryKind::Transfer => EntryKind::Expense,
+        };
+    }
+
+    /// Suggestions for the category being typed; empty unless the category
+    /// field has focus.
+    fn suggestions(&self) -> Vec<&Category> {
+        if self.focus != AddEntryFocus::Category {
+            return Vec::new();
+        }
+        matching_categories(&self.categories, self.category_input.value())
+    }
+
+    /// Moves the highlighted suggestion; returns `false` when there is no
+    /// list to move in, so the key moves focus instead.
+    fn move_suggestion(&mut self, down: bool) -> bool {
+        let count = self.suggestions().len().min(MAX_SUGGESTIONS);
+        if count == 0 {
+            return false;
+        }
+        self.suggestion = if down {
+            (self.suggestion + 1) % count
+        } else {
+            (self.suggestion + count - 1) % count
+        };
+        true
+    }
+
+    /// Fills the category field with the highlighted suggestion, if any.
+    fn accept_suggestion(&mut self) {
+        let chosen = self
+            .suggestions()
+            .get(self.suggestion)
+            .map(|category| category.as_str().to_string());
+        if let Some(chosen) = chosen {
+            self.category_input.set_value(chosen);
+        }
+        self.suggestion = 0;
+    }
+
+    fn focused_input(&mut self) -> Option<&mut TextInput> {
+        match self.focus {
+            AddEntryFocus::Amount => Some(&mut self.amount_input),
+            AddEntryFocus::Category => Some(&mut self.category_input),
+            AddEntryFocus::Date => Some(&mut self.date_input),
+            AddEntryFocus::Note => Some(&mut self.note_input),
+            _ => None,
+        }
+    }
+
+    fn activate(&mut self, repo: &mut dyn EntryRepository) -> ScreenResult {
+        match self.focus {
+            AddEntryFocus::Kind => {
+                self.toggle_kind();
+                ScreenResult::None
+            }
+            AddEntryFocus::Note => {
+                self.note_input.insert_newline();
+                ScreenResult::None
+            }
+            AddEntryFocus::SaveButton => self.save(repo),
+            AddEntryFocus::CancelButton => ScreenResult::Back,
+            _ => {
+                self.focus_next();
+                ScreenResult::None
+            }
+        }
+    }
+
+    fn save(&mut self, repo: &mut dyn EntryRepository) -> ScreenResult {
+        let result = self.build_entry().and_then(|entry| {
+            repo.add(entry)
+                .inspect_err(|e| log::error!("adding entry failed: {e}"))
+        });
+        match result {
+            Ok(added) => ScreenResult::BackToEntry(added.id),
+            Err(e) => {
+                self.error_message = Some(user_message(&e));
+                ScreenResult::None
+            }
+        }
+    }
+
+    fn build_entry(&self) -> Result<NewEntry, DomainError> {
+        let amount = self.amount()?;
+        let category = Category::new(self.category_input.value())?;
+        let occurred_on = parse_relative_date(self.date_input.value(), Local::now().date_naive())?;
+
+        let mut builder = NewEntryBuilder::new()
+            .kind(self.kind)
+            .amount(amount)
+            .category(category)
+            .occurred_on(occurred_on);
+        let note = self.note_input.value();
+        if !note.trim().is_empty() {
+            builder = builder.note(note);
+        }
+        builder.build()
+    }
+
+    /// A plain number such as `1,234.50` goes through the strict
+    /// `parse_amount`; anything else is evaluated as an expression.
+    fn amount(&self) -> Result<Money<'static, iso::Currency>, DomainError> {
+        let input = self.amount_input.value();
+        let plain = input
+            .trim()
+            .chars()
+            .all(|ch| ch.is_ascii_digit() || ch == ',' || ch == '.');
+        if plain {
+            parse_amount(input, self.currency)
+        } else {
+            evaluate_amount(input, self.currency)
+        }
+    }
+
+    /// Draws the suggestion list just below the category field (`row`).
+    fn render_suggestions(&self, frame: &mut ratatui::Frame<'_>, row: Rect, theme: &Theme) {
+        let suggestions = self.suggestions();
+        if suggestions.is_empty() {
+            return;
+        }
+        let shown = suggestions.len().min(MAX_SUGGESTIONS);
+        let area = Rect {
+            x: row.x + 12,
+            y: row.y + 1,
+            width: 30,
+            height: shown as u16 + 2,
+        }
+        .intersection(frame.area());
+        frame.render_widget(Clear, area);
+
+        let lines: Vec<Line> = suggestions
+            .iter()
+            .take(shown)
+            .enumerate()
+            .map(|(idx, category)| {
+                let style = if idx == self.suggestion {
+                    theme.focus
+                } else {
+                    Style::default()
+                };
+                Line::from(Span::styled(format!(" {}", category.as_str()), style))
+            })
+            .collect();
+        let list =
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).style(theme.popup));
+        frame.render_widget(list, area);
+    }
+}
+
+impl Screen for AddEntryScreen {
+    fn init(&mut self, repo: &mut dyn EntryRepository) -> Result<(), DomainError> {
+        self.reset();
+        self.currency = repo.default_currency();
+        self.categories = repo.categories()?;
+        Ok(())
+    }
+
+    fn captures_text(&self) -> bool {
+        matches!(
+            self.focus,
+            AddEntryFocus::Amount
+                | AddEntryFocus::Category
+                | AddEntryFocus::Date
+                | AddEntryFocus::Note
+        )
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame<'_>, theme: &Theme) {
+        let area = frame.area();
+        frame.render_widget(Clear, area);
+
+        let form_area = centered_rect(area, 60, NOTE_HEIGHT + 10);
+        let form_block = Block::default().title("Add Entry").borders(Borders::ALL);
+        let inner = form_block.inner(form_area);
+        frame.render_widget(form_block, form_area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),           // Kind
+                Constraint::Length(1),           // Amount
+                Constraint::Length(1),           // Category
+                Constraint::Length(1),           // Date
+                Constraint::Length(NOTE_HEIGHT), // Note
+                Constraint::Length(1),           // Spacer
+                Constraint::Length(1),           // Buttons
+                Constraint::Length(1),           // Error
+            ])
+            .split(inner);
+
+        let label_style = Style::default().add_modifier(Modifier::BOLD);
+        let style_for = |focus: AddEntryFocus| {
+            if self.focus == focus {
+                theme.focus
+            } else {
+                theme.field
+            }
+        };
+
+        let kind_label = match self.kind {
+            EntryKind::Expense => "< Expense >",
+            EntryKind::Income => "< Income >",
+            EntryKind::Transfer => "< Transfer >",
+        };
+        render_field(
+            frame,
+            rows[0],
+            "Kind",
+            Paragraph::new(Line::from(Span::styled(
+                kind_label,
+                style_for(AddEntryFocus::Kind),
+            ))),
+            label_style,
+        );
+        let fields = [
+            ("Amount", &self.amount_input, AddEntryFocus::Amount),
+            ("Category", &self.category_input, AddEntryFocus::Category),
+            ("Date", &self.date_input, AddEntryFocus::Date),
+        ];
+        for (row, (label, input, focus)) in rows[1..].iter().zip(fields) {
+            let line = input.bracketed_line(style_for(focus), self.focus == focus);
+            render_field(frame, *row, label, Paragraph::new(line), label_style);
+        }
+
+        let note_lines = self.note_input.lines(
+            style_for(AddEntryFocus::Note),
+            self.focus == AddEntryFocus::Note,
+        );
+        render_field(
+            frame,
+            rows[4],
+            "Note",
+            Paragraph::new(note_lines),
+            label_style,
+        );
+
+        let buttons = Paragraph::new(Line::from(vec![
+            Span::styled(" Save ", style_for(AddEntryFocus::SaveButton)),
+            Span::raw("  "),
+            Span::styled(" Cancel ", style_for(AddEntryFocus::CancelButton)),
+        ]));
+        frame.render_widget(buttons, rows[6]);
+
+        if let Some(err) = &self.error_message {
+            let err_line = Line::from(Span::styled(err, theme.error));
+            frame.render_widget(Paragraph::new(err_line), rows[7]);
+        }
+
+        self.render_suggestions(frame, rows[2], theme);
+    }
+
+    fn handle_action(&mut self, action: Action, repo: &mut dyn EntryRepository) -> ScreenResult {
+        match action {
+            Action::Quit => ScreenResult::Quit,
+            Action::Cancel => ScreenResult::Back,
+            Action::FocusNext => {
+                if self.focus == AddEntryFocus::Category {
+                    self.accept_suggestion();
+                }
+                self.focus_next();
+                ScreenResult::None
+            }
+            // Up/down move between note lines before leaving the field.
+            Action::NavUp | Action::NavDown
+                if self.focus == AddEntryFocus::Note
+                    && self.note_input.handle_action(action.clone()) =>
+            {
+                ScreenResult::None
+            }
+            Action::NavDown => {
+                if !self.move_suggestion(true) {
+                    self.focus_next();
+                }
+                ScreenResult::None
+            }
+            Action::FocusPrev => {
+                self.focus_prev();
+                ScreenResult::None
+            }
+            Action::NavUp => {
+                if !self.move_suggestion(false) {
+                    self.focus_prev();
+                }
+                ScreenResult::None
+            }
+            Action::NavLeft | Action::NavRight if self.focus == AddEntryFocus::Kind => {
+                self.toggle_kind();
+                ScreenResult::None
+            }
+            Action::Activate => self.activate(repo),
+            Action::InputChar(_)
+            | Action::Paste(_)
+            | Action::Backspace
+            | Action::NavLeft
+            | Action::NavRight
+            | Action::LineStart
+            | Action::LineEnd => {
+                if matches!(
+                    action,
+                    Action::InputChar(_) | Action::Paste(_) | Action::Backspace
+                ) {
+                    self.error_message = None;
+                    self.suggestion = 0;
+                }
+                if let Some(input) = self.focused_input() {
+                    input.handle_action(action);
+                }
+                ScreenResult::None
+            }
+            _ => ScreenResult::None,
+        }
+    }
+}
+
+/// Categories containing `input` (case-insensitive), in their original
+/// order. Empty input, or input that already names the only match exactly,
+/// gives no suggestions.
+fn matching_categories<'a>(categories: &'a [Category], input: &str) -> Vec<&'a Category> {
+    let needle = input.trim().to_lowercase();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let matches: Vec<&Category> = categories
+        .iter()
+        .filter(|category| category.as_str().to_lowercase().contains(&needle))
+        .collect();
+    match matches.as_slice() {
+        [only] if only.as_str().to_lowercase() == needle => Vec::new(),
+        _ => matches,
+    }
+}
+
+fn render_field(
+    frame: &mut ratatui::Frame<'_>,
+    area: ratatui::layout::Rect,
+    label: &str,
+    value: Paragraph<'_>,
+    label_style: Style,
+) {
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(12), Constraint::Min(0)])
+        .split(area);
+
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(label, label_style))),
+        cols[0],
+    );
+    frame.render_widget(value, cols[1]);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AddEntryFocus {
+    Kind,
+    Amount,
+    Category,
+    Date,
+    Note,
+    SaveButton,
+    CancelButton,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::EntryFilter;
+    use storage::SqliteRepository;
+
+    fn type_text(screen: &mut AddEntryScreen, repo: &mut dyn EntryRepository, text: &str) {
+        for ch in text.chars() {
+            screen.handle_action(Action::InputChar(ch), repo);
+        }
+    }
+
+    fn categories(names: &[&str]) -> Vec<Category> {
+        names
+            .iter()
+            .map(|name| Category::new(*name).unwrap())
+            .collect()
+    }
+
+    fn names(matches: Vec<&Category>) -> Vec<&str> {
+        matches.into_iter().map(Category::as_str).collect()
+    }
+
+    #[test]
+    fn category_suggestions_match_substrings_ignoring_case() {
+        let all = categories(&["Food", "fast food", "rent", "Seafood"]);
+        assert_eq!(
+            names(matching_categories(&all, "FOO")),
+            ["Food", "fast food", "Seafood"]
+        );
+        assert_eq!(names(matching_categories(&all, " ren")), ["rent"]);
+        assert!(matching_categories(&all, "travel").is_empty());
+        assert!(matching_categories(&all, "RENT").is_empty());
+    }
+
+    #[test]
+    fn empty_category_input_has_no_suggestions() {
+        let all = categories(&["food", "rent"]);
+        assert!(matching_categories(&all, "").is_empty());
+        assert!(matching_categories(&all, "   ").is_empty());
+        assert!(matching_categories(&[], "food").is_empty());
+    }
+
+    #[test]
+    fn tab_accepts_the_highlighted_suggestion() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        for category in ["groceries", "gifts"] {
+            repo.add(
+                NewEntryBuilder::new()
+                    .amount(rusty_money::Money::from_minor(100, iso::USD))
+                    .category(Category::new(category).unwrap())
+                    .build()
+                    .expect("entry built"),
+            )
+            .expect("entry added");
+        }
+        let mut screen = AddEntryScreen::new();
+        screen.init(&mut repo).expect("init");
+        screen.handle_action(Action::FocusNext, &mut repo);
+        screen.handle_action(Action::FocusNext, &mut repo);
+
+        type_text(&mut screen, &mut repo, "G");
+        assert_eq!(names(screen.suggestions()), ["gifts", "groceries"]);
+        screen.handle_action(Action::NavDown, &mut repo);
+        screen.handle_action(Action::FocusNext, &mut repo);
+        assert_eq!(screen.category_input.value(), "groceries");
+        assert_eq!(screen.focus, AddEntryFocus::Date);
+
+        // Unknown text stays as typed and becomes a new category.
+        screen.handle_action(Action::FocusPrev, &mut repo);
+        type_text(&mut screen, &mut repo, "!");
+        screen.handle_action(Action::FocusNext, &mut repo);
+        assert_eq!(screen.category_input.value(), "groceries!");
+    }
+
+    #[test]
+    fn date_field_accepts_shortcuts() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut screen = AddEntryScreen::new();
+        screen.init(&mut repo).expect("init");
+        screen.amount_input.set_value("5");
+        screen.category_input.set_value("food");
+
+        screen.date_input.set_value("yesterday");
+        let entry = screen.build_entry().expect("entry built");
+        assert_eq!(
+            entry.occurred_on,
+            Local::now().date_naive().pred_opt().expect("date")
+        );
+
+        screen.date_input.set_value("last week");
+        assert!(matches!(
+            screen.build_entry(),
+            Err(DomainError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn enter_in_note_inserts_newline_and_is_stored() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut screen = AddEntryScreen::new();
+        screen.init(&mut repo).expect("init");
+
+        screen.handle_action(Action::FocusNext, &mut repo);
+        type_text(&mut screen, &mut repo, "12.50");
+        screen.handle_action(Action::FocusNext, &mut repo);
+        type_text(&mut screen, &mut repo, "food");
+        screen.handle_action(Action::FocusNext, &mut repo);
+        screen.handle_action(Action::FocusNext, &mut repo);
+        type_text(&mut screen, &mut repo, "lunch");
+        screen.handle_action(Action::Activate, &mut repo);
+        type_text(&mut screen, &mut repo, "with team");
+        assert_eq!(screen.note_input.value(), "lunch\nwith team");
+
+        // Up moves within the note; on the first line it leaves the field.
+        screen.handle_action(Action::NavUp, &mut repo);
+        assert_eq!(screen.focus, AddEntryFocus::Note);
+        screen.handle_action(Action::NavUp, &mut repo);
+        assert_eq!(screen.focus, AddEntryFocus::Date);
+        screen.handle_action(Action::NavDown, &mut repo);
+
+        screen.handle_action(Action::FocusNext, &mut repo);
+        let result = screen.handle_action(Action::Activate, &mut repo);
+        assert!(matches!(result, ScreenResult::BackToEntry(_)));
+
+        let entries = repo.list(EntryFilter::default()).expect("entries listed");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].note.as_deref(), Some("lunch\nwith team"));
+    }
+
+    #[test]
+    fn paste_lands_in_the_focused_field() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut screen = AddEntryScreen::new();
+        screen.init(&mut repo).expect("init");
+
+        screen.handle_action(Action::FocusNext, &mut repo);
+        screen.handle_action(Action::Paste("1,234.50".to_string()), &mut repo);
+        for _ in 0..3 {
+            screen.handle_action(Action::FocusNext, &mut repo);
+        }
+        screen.handle_action(Action::Paste("flight\nto Berlin".to_string()), &mut repo);
+
+        assert_eq!(screen.amount_input.value(), "1,234.50");
+        assert_eq!(screen.category_input.value(), "");
+        assert_eq!(screen.note_input.value(), "flight\nto Berlin");
+
+        screen.category_input.set_value("travel");
+        screen.handle_action(Action::FocusNext, &mut repo);
+        let result = screen.handle_action(Action::Activate, &mut repo);
+        assert!(matches!(result, ScreenResult::BackToEntry(_)));
+
+        let entries = repo.list(EntryFilter::default()).expect("entries listed");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].amount, Money::from_minor(123_450, iso::USD));
+    }
+
+    #[test]
+    fn amount_field_rejects_misplaced_commas() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        let mut screen = AddEntryScreen::new();
+        screen.init(&mut repo).expect("init");
+        screen.category_input.set_value("food");
+
+        screen.amount_input.set_value("1,2,3");
+        assert!(matches!(
+            screen.build_entry(),
+            Err(DomainError::InvalidData(_))
+        ));
+
+        screen.amount_input.set_value("1,000 / 4");
+        assert!(screen.build_entry().is_err());
+
+        screen.amount_input.set_value("1000 / 4");
+        let entry = screen.build_entry().expect("entry built");
+        assert_eq!(entry.amount, Money::from_minor(25_000, iso::USD));
+    }
+}
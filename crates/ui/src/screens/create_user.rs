@@ -1,27 +1,31 @@
-use domain::EntryRepository;
+use domain::{EntryRepository, validate_password};
 use ratatui::layout::{Constraint, Direction, Layout};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 
-use super::{Screen, ScreenId, ScreenResult};
+use super::{Screen, ScreenResult, user_message};
 use crate::event::Action;
 use crate::layout::centered_rect;
+use crate::theme::Theme;
+use crate::widgets::TextInput;
 
 pub struct CreateUserScreen {
     focus: CreateUserFocus,
-    login_input: String,
-    password_input: String,
-    repeat_input: String,
+    login_input: TextInput,
+    password_input: TextInput,
+    repeat_input: TextInput,
+    error_message: Option<String>,
 }
 
 impl CreateUserScreen {
     pub fn new() -> Self {
         Self {
             focus: CreateUserFocus::Login,
-            login_input: String::new(),
-            password_input: String::new(),
-            repeat_input: String::new(),
+            login_input: TextInput::new(),
+            password_input: TextInput::masked(),
+            repeat_input: TextInput::masked(),
+            error_message: None,
         }
     }
 
@@ -45,46 +49,114 @@ impl CreateUserScreen {
         };
     }
 
-    fn activate(&self) -> ScreenResult {
+    fn activate(&mut self, repo: &mut dyn EntryRepository) -> ScreenResult {
         match self.focus {
-            CreateUserFocus::CreateButton => {
-                // TODO: Create user logic.
-                ScreenResult::Go(ScreenId::Login)
-            }
-            CreateUserFocus::BackButton => ScreenResult::Go(ScreenId::Login),
+            CreateUserFocus::CreateButton => self.create_user(repo),
+            CreateUserFocus::BackButton => ScreenResult::Back,
             _ => ScreenResult::None,
         }
     }
 
-    fn input_char(&mut self, ch: char) {
-        match self.focus {
-            CreateUserFocus::Login => self.login_input.push(ch),
-            CreateUserFocus::Password => self.password_input.push(ch),
-            CreateUserFocus::RepeatPassword => self.repeat_input.push(ch),
-            _ => {}
+    fn toggle_reveal(&mut self) {
+        // Both password fields switch together so they can be compared.
+        let revealed = !self.password_input.is_revealed();
+        self.password_input.set_revealed(revealed);
+        self.repeat_input.set_revealed(revealed);
+    }
+
+    fn create_user(&mut self, repo: &mut dyn EntryRepository) -> ScreenResult {
+        let login = self.login_input.value().trim();
+        let password = self.password_input.value();
+        if login.is_empty() || password.is_empty() {
+            self.error_message = Some("Login and password required".to_string());
+            return ScreenResult::None;
+        }
+        if password != self.repeat_input.value() {
+            self.error_message = Some("Passwords do not match".to_string());
+            return ScreenResult::None;
+        }
+        if let Err(e) = validate_password(password) {
+            self.error_message = Some(user_message(&e));
+            return ScreenResult::None;
+        }
+
+        match repo.create_user(login, password) {
+            Ok(user) => ScreenResult::UserCreated(user.username),
+            Err(e) => {
+                log::warn!("creating user {login} failed: {e}");
+                self.error_message = Some(user_message(&e));
+                ScreenResult::None
+            }
         }
     }
 
-    fn backspace(&mut self) {
+    #[cfg(test)]
+    pub(crate) fn is_blank(&self) -> bool {
+        [&self.login_input, &self.password_input, &self.repeat_input]
+            .iter()
+            .all(|input| input.value().is_empty())
+            && self.error_message.is_none()
+    }
+
+    fn focused_input(&mut self) -> Option<&mut TextInput> {
         match self.focus {
-            CreateUserFocus::Login => self.login_input.pop(),
-            CreateUserFocus::Password => self.password_input.pop(),
-            CreateUserFocus::RepeatPassword => self.repeat_input.pop(),
+            CreateUserFocus::Login => Some(&mut self.login_input),
+            CreateUserFocus::Password => Some(&mut self.password_input),
+            CreateUserFocus::RepeatPassword => Some(&mut self.repeat_input),
             _ => None,
-        };
+        }
     }
 
     fn nav_up(&mut self) {
-        self.focus_prev();
+        self.focus = match self.focus {
+            CreateUserFocus::CreateButton | CreateUserFocus::BackButton => {
+                CreateUserFocus::RepeatPassword
+            }
+            CreateUserFocus::Login => CreateUserFocus::CreateButton,
+            _ => {
+                self.focus_prev();
+                return;
+            }
+        };
     }
 
     fn nav_down(&mut self) {
-        self.focus_next();
+        self.focus = match self.focus {
+            CreateUserFocus::CreateButton | CreateUserFocus::BackButton => CreateUserFocus::Login,
+            _ => {
+                self.focus_next();
+                return;
+            }
+        };
+    }
+
+    /// Left/Right only move between the buttons; fields keep their focus
+    /// (the cursor moves inside the field instead).
+    fn nav_horizontal(&mut self) {
+        self.focus = match self.focus {
+            CreateUserFocus::CreateButton => CreateUserFocus::BackButton,
+            CreateUserFocus::BackButton => CreateUserFocus::CreateButton,
+            other => other,
+        };
     }
 }
 
 impl Screen for CreateUserScreen {
-    fn render(&mut self, frame: &mut ratatui::Frame<'_>) {
+    fn init(&mut self, _repo: &mut dyn EntryRepository) -> Result<(), domain::DomainError> {
+        // `App` reuses this screen, so start from a blank, masked form rather
+        // than the previous account's name and password.
+        *self = Self::new();
+        Ok(())
+    }
+
+    fn captures_text(&self) -> bool {
+        matches!(
+            self.focus,
+            CreateUserFocus::Login | CreateUserFocus::Password | CreateUserFocus::RepeatPassword
+        )
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame<'_>, theme: &Theme) {
         let area = frame.area();
         frame.render_widget(Clear, area);
 
@@ -103,62 +175,35 @@ impl Screen for CreateUserScreen {
                 Constraint::Length(1),
                 Constraint::Length(1),
                 Constraint::Length(1),
+                Constraint::Length(1),
             ])
             .split(inner);
 
         let label_style = Style::default().add_modifier(Modifier::BOLD);
-        let field_style = Style::default().fg(Color::White);
-        let focus_style = Style::default().fg(Color::Black).bg(Color::White);
-        let field_focus_style = Style::default().fg(Color::Black).bg(Color::White);
-
-        render_field(
-            frame,
-            rows[0],
-            "Login",
-            Line::from(vec![Span::styled(
-                format!("[ {} ]", self.login_input),
-                if self.focus == CreateUserFocus::Login {
-                    field_focus_style
-                } else {
-                    field_style
-                },
-            )]),
-            label_style,
-            self.focus == CreateUserFocus::Login,
-            focus_style,
-        );
-        render_field(
-            frame,
-            rows[1],
-            "Password",
-            Line::from(vec![Span::styled(
-                format!("[ {} ]", "*".repeat(self.password_input.len())),
-                if self.focus == CreateUserFocus::Password {
-                    field_focus_style
-                } else {
-                    field_style
-                },
-            )]),
-            label_style,
-            self.focus == CreateUserFocus::Password,
-            focus_style,
-        );
-        render_field(
-            frame,
-            rows[2],
-            "Repeat",
-            Line::from(vec![Span::styled(
-                format!("[ {} ]", "*".repeat(self.repeat_input.len())),
-                if self.focus == CreateUserFocus::RepeatPassword {
-                    field_focus_style
-                } else {
-                    field_style
-                },
-            )]),
-            label_style,
-            self.focus == CreateUserFocus::RepeatPassword,
-            focus_style,
-        );
+        let (field_style, focus_style) = (theme.field, theme.focus);
+
+        let fields = [
+            ("Login", &self.login_input, CreateUserFocus::Login),
+            ("Password", &self.password_input, CreateUserFocus::Password),
+            (
+                "Repeat",
+                &self.repeat_input,
+                CreateUserFocus::RepeatPassword,
+            ),
+        ];
+        for (row, (label, input, focus)) in rows.iter().zip(fields) {
+            let focused = self.focus == focus;
+            let style = if focused { focus_style } else { field_style };
+            render_field(
+                frame,
+                *row,
+                label,
+                input.bracketed_line(style, focused),
+                label_style,
+                focused,
+                focus_style,
+            );
+        }
 
         let normal = Style::default();
         let create_style = match self.focus {
@@ -176,26 +221,66 @@ impl Screen for CreateUserScreen {
             Span::styled(" Back ", back_style),
         ]));
         frame.render_widget(buttons, rows[4]);
+
+        if let Some(err) = &self.error_message {
+            let err_line = Line::from(Span::styled(err, theme.error));
+            frame.render_widget(Paragraph::new(err_line), rows[5]);
+        }
     }
 
-    fn handle_action(&mut self, action: Action, _repo: &mut dyn EntryRepository) -> ScreenResult {
+    fn handle_action(&mut self, action: Action, repo: &mut dyn EntryRepository) -> ScreenResult {
         match action {
             Action::Quit => ScreenResult::Quit,
-            Action::FocusNext | Action::NavRight | Action::NavDown => {
+            Action::Cancel => ScreenResult::Back,
+            Action::ToggleReveal
+                if matches!(
+                    self.focus,
+                    CreateUserFocus::Password | CreateUserFocus::RepeatPassword
+                ) =>
+            {
+                self.toggle_reveal();
+                ScreenResult::None
+            }
+            Action::FocusNext => {
+                self.focus_next();
+                ScreenResult::None
+            }
+            Action::FocusPrev => {
+                self.focus_prev();
+                ScreenResult::None
+            }
+            Action::NavDown => {
                 self.nav_down();
                 ScreenResult::None
             }
-            Action::FocusPrev | Action::NavLeft | Action::NavUp => {
+            Action::NavUp => {
                 self.nav_up();
                 ScreenResult::None
             }
-            Action::Activate => self.activate(),
-            Action::InputChar(ch) => {
-                self.input_char(ch);
+            Action::NavLeft | Action::NavRight => {
+                match self.focused_input() {
+                    Some(input) => {
+                        input.handle_action(action);
+                    }
+                    None => self.nav_horizontal(),
+                }
                 ScreenResult::None
             }
-            Action::Backspace => {
-                self.backspace();
+            Action::Activate => self.activate(repo),
+            Action::InputChar(_)
+            | Action::Paste(_)
+            | Action::Backspace
+            | Action::LineStart
+            | Action::LineEnd => {
+                if matches!(
+                    action,
+                    Action::InputChar(_) | Action::Paste(_) | Action::Backspace
+                ) {
+                    self.error_message = None;
+                }
+                if let Some(input) = self.focused_input() {
+                    input.handle_action(action);
+                }
                 ScreenResult::None
             }
             _ => ScreenResult::None,
@@ -235,3 +320,155 @@ enum CreateUserFocus {
     CreateButton,
     BackButton,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::UserRepository;
+
+    #[test]
+    fn left_right_switch_between_buttons() {
+        let mut screen = CreateUserScreen::new();
+        screen.focus = CreateUserFocus::CreateButton;
+
+        screen.nav_horizontal();
+        assert_eq!(screen.focus, CreateUserFocus::BackButton);
+        screen.nav_horizontal();
+        assert_eq!(screen.focus, CreateUserFocus::CreateButton);
+    }
+
+    #[test]
+    fn left_right_keep_field_focus() {
+        let mut screen = CreateUserScreen::new();
+        screen.focus = CreateUserFocus::Password;
+
+        screen.nav_horizontal();
+        assert_eq!(screen.focus, CreateUserFocus::Password);
+    }
+
+    #[test]
+    fn left_right_edit_inside_fields() {
+        let mut repo = storage::SqliteRepository::new_in_memory().expect("repo created");
+        let mut screen = CreateUserScreen::new();
+        for ch in "bb".chars() {
+            screen.handle_action(Action::InputChar(ch), &mut repo);
+        }
+        screen.handle_action(Action::NavLeft, &mut repo);
+        screen.handle_action(Action::InputChar('o'), &mut repo);
+        screen.handle_action(Action::NavRight, &mut repo);
+        screen.handle_action(Action::InputChar('y'), &mut repo);
+
+        assert_eq!(screen.login_input.value(), "boby");
+        assert_eq!(screen.focus, CreateUserFocus::Login);
+    }
+
+    fn fill_form(screen: &mut CreateUserScreen, login: &str, password: &str, repeat: &str) {
+        screen.login_input.set_value(login);
+        screen.password_input.set_value(password);
+        screen.repeat_input.set_value(repeat);
+        screen.focus = CreateUserFocus::CreateButton;
+    }
+
+    #[test]
+    fn create_button_validates_and_creates_user() {
+        let mut repo = storage::SqliteRepository::new_in_memory().expect("repo created");
+        let mut screen = CreateUserScreen::new();
+
+        fill_form(&mut screen, "alice", "secret", "secreT");
+        let result = screen.handle_action(Action::Activate, &mut repo);
+        assert!(matches!(result, ScreenResult::None));
+        assert_eq!(
+            screen.error_message.as_deref(),
+            Some("Passwords do not match")
+        );
+        assert!(repo.list_users().unwrap().is_empty());
+
+        fill_form(&mut screen, "alice", "secret", "secret");
+        let result = screen.handle_action(Action::Activate, &mut repo);
+        assert!(matches!(result, ScreenResult::None));
+        assert_eq!(
+            screen.error_message.as_deref(),
+            Some("Password must be at least 8 characters")
+        );
+        assert!(repo.list_users().unwrap().is_empty());
+
+        fill_form(&mut screen, "alice", "secret-42", "secret-42");
+        let result = screen.handle_action(Action::Activate, &mut repo);
+        assert!(matches!(result, ScreenResult::UserCreated(name) if name == "alice"));
+        assert_eq!(repo.list_users().unwrap(), ["alice"]);
+
+        let mut screen = CreateUserScreen::new();
+        fill_form(&mut screen, "alice", "other-pw1", "other-pw1");
+        let result = screen.handle_action(Action::Activate, &mut repo);
+        assert!(matches!(result, ScreenResult::None));
+        assert!(screen.error_message.is_some());
+    }
+
+    #[test]
+    fn reveal_only_applies_to_password_fields() {
+        let mut repo = storage::SqliteRepository::new_in_memory().expect("repo created");
+        let mut screen = CreateUserScreen::new();
+
+        screen.handle_action(Action::ToggleReveal, &mut repo);
+        assert!(!screen.password_input.is_revealed());
+
+        screen.handle_action(Action::FocusNext, &mut repo);
+        screen.handle_action(Action::ToggleReveal, &mut repo);
+        assert!(screen.password_input.is_revealed());
+        assert!(screen.repeat_input.is_revealed());
+
+        screen.init(&mut repo).expect("init");
+        assert!(!screen.password_input.is_revealed());
+        assert!(!screen.repeat_input.is_revealed());
+    }
+
+    #[test]
+    fn cancel_returns_to_login() {
+        let mut repo = storage::SqliteRepository::new_in_memory().expect("repo created");
+        let mut screen = CreateUserScreen::new();
+        let result = screen.handle_action(Action::Cancel, &mut repo);
+        assert!(matches!(result, ScreenResult::Back));
+
+        screen.focus = CreateUserFocus::BackButton;
+        let result = screen.handle_action(Action::Activate, &mut repo);
+        assert!(matches!(result, ScreenResult::Back));
+    }
+
+    #[test]
+    fn tab_cycles_through_every_control() {
+        let mut repo = storage::SqliteRepository::new_in_memory().expect("repo created");
+        let mut screen = CreateUserScreen::new();
+        let order = [
+            CreateUserFocus::Password,
+            CreateUserFocus::RepeatPassword,
+            CreateUserFocus::CreateButton,
+            CreateUserFocus::BackButton,
+            CreateUserFocus::Login,
+        ];
+        for expected in order {
+            screen.handle_action(Action::FocusNext, &mut repo);
+            assert_eq!(screen.focus, expected);
+        }
+        for expected in order.iter().rev().skip(1) {
+            screen.handle_action(Action::FocusPrev, &mut repo);
+            assert_eq!(screen.focus, *expected);
+        }
+        screen.handle_action(Action::FocusPrev, &mut repo);
+        assert_eq!(screen.focus, CreateUserFocus::Login);
+    }
+
+    #[test]
+    fn up_down_move_between_fields_and_buttons() {
+        let mut screen = CreateUserScreen::new();
+
+        screen.nav_down();
+        screen.nav_down();
+        assert_eq!(screen.focus, CreateUserFocus::RepeatPassword);
+        screen.nav_down();
+        assert_eq!(screen.focus, CreateUserFocus::CreateButton);
+
+        screen.nav_horizontal();
+        screen.nav_up();
+        assert_eq!(screen.focus, CreateUserFocus::RepeatPassword);
+    }
+}
@@ -1,36 +1,426 @@
-use domain::{DomainError, Entry, EntryFilter, EntryRepository};
-use ratatui::layout::Alignment;
-use ratatui::style::{Color, Modifier, Style};
+use std::collections::HashMap;
+
+use chrono::{Days, Local, NaiveDate};
+use domain::{
+    Category, DEFAULT_CURRENCY, DomainError, Entry, EntryFilter, EntryId, EntryKind,
+    EntryRepository, NewEntry, Totals, minor_units, month_bounds,
+};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Sparkline};
+use rusty_money::{Money, iso};
 
-use super::{Screen, ScreenResult};
+use super::{Screen, ScreenId, ScreenResult, user_message};
+use crate::clipboard::Clipboard;
 use crate::event::Action;
-use crate::layout::main_chunks;
+use crate::format::{entry_summary, format_amount, signed_amount};
+use crate::layout::{centered_rect, main_chunks};
+use crate::theme::Theme;
+use crate::toast::ToastLevel;
+use crate::widgets::{TextInput, first_line};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// Days of spending shown in the header sparkline.
+const SPARKLINE_DAYS: u64 = 7;
+/// Columns the sparkline takes when the header is wide enough.
+const SPARKLINE_WIDTH: u16 = 21;
 
 pub struct DashboardScreen {
     entries: Vec<Entry>,
+    /// Balance after each entry in `entries`, in minor units of that
+    /// entry's currency.
+    balances: Vec<i64>,
     list_state: ListState,
+    /// Entry to select on the next refresh instead of the current one.
+    select_next: Option<EntryId>,
+    totals: Totals,
+    /// Currency the totals are shown in, taken from the repository.
+    currency: &'static iso::Currency,
+    /// Expenses for each of the last `SPARKLINE_DAYS` days, oldest first.
+    recent_spending: Vec<u64>,
+    /// Show expenses as negative and income as positive amounts.
+    signed_amounts: bool,
+    /// How entry dates are listed; filters are always typed as `DATE_FORMAT`.
+    date_format: String,
+    filter: EntryFilter,
+    /// Category filter being typed after `/`; `None` when the bar is closed.
+    category_input: Option<TextInput>,
+    date_range: Option<DateRangeDialog>,
+    confirm_delete: bool,
+    /// The most recently deleted (archived) entry, restorable once with `u`.
+    last_deleted: Option<EntryId>,
+    clipboard: Clipboard,
+    error_message: Option<String>,
 }
 
 impl DashboardScreen {
     pub fn new() -> Self {
         Self {
             entries: Vec::new(),
+            balances: Vec::new(),
             list_state: ListState::default(),
+            select_next: None,
+            totals: Totals::default(),
+            currency: DEFAULT_CURRENCY,
+            recent_spending: Vec::new(),
+            signed_amounts: false,
+            date_format: DATE_FORMAT.to_string(),
+            filter: EntryFilter::default(),
+            category_input: None,
+            date_range: None,
+            confirm_delete: false,
+            last_deleted: None,
+            clipboard: Clipboard::default(),
+            error_message: None,
+        }
+    }
+
+    pub fn set_signed_amounts(&mut self, signed: bool) {
+        self.signed_amounts = signed;
+    }
+
+    pub fn set_date_format(&mut self, format: &str) {
+        self.date_format = format.to_string();
+    }
+
+    /// Selects `id` when the list is next refreshed, e.g. a just-added entry.
+    pub fn select_on_refresh(&mut self, id: EntryId) {
+        self.select_next = Some(id);
+    }
+
+    /// The amount as listed, signed by kind when `signed_amounts` is on.
+    fn shown_amount(&self, entry: &Entry) -> Money<'static, iso::Currency> {
+        if self.signed_amounts {
+            signed_amount(entry.kind, entry.amount)
+        } else {
+            entry.amount
         }
     }
 
     fn refresh_entries(&mut self, repo: &dyn EntryRepository) -> Result<(), DomainError> {
-        // TODO: Pagination? For now list all.
-        self.entries = repo.list(EntryFilter::default())?;
+        // Every matching row is loaded on purpose: the running balance of
+        // each row depends on all the entries before it.
+        let selected_id = self
+            .select_next
+            .take()
+            .or_else(|| self.selected_entry().map(|entry| entry.id));
+        self.entries = repo.list(self.filter.clone())?;
+        self.balances = running_balances(&self.entries);
+        self.totals = repo.totals(self.filter.clone())?;
+        self.currency = repo.default_currency();
+        let today = Local::now().date_naive();
+        let first_day = today - Days::new(SPARKLINE_DAYS - 1);
+        self.recent_spending = repo
+            .daily_expense_totals(first_day, today)?
+            .into_iter()
+            .map(|(_, cents)| cents.max(0) as u64)
+            .collect();
         if self.entries.is_empty() {
             self.list_state.select(None);
-        } else if self.list_state.selected().is_none() {
-            self.list_state.select(Some(0));
+        } else {
+            // Follow the selected entry if it is still listed; otherwise keep
+            // the index in range (e.g. after it was deleted).
+            let last = self.entries.len() - 1;
+            let selected = selected_id
+                .and_then(|id| self.entries.iter().position(|entry| entry.id == id))
+                .unwrap_or_else(|| self.list_state.selected().map_or(0, |i| i.min(last)));
+            self.list_state.select(Some(selected));
+        }
+        Ok(())
+    }
+
+    fn open_category_filter(&mut self) {
+        let mut input = TextInput::new();
+        if let Some(category) = &self.filter.category {
+            input.set_value(category.as_str());
+        }
+        self.category_input = Some(input);
+    }
+
+    fn set_category_filter(&mut self, category: Option<Category>, repo: &dyn EntryRepository) {
+        self.category_input = None;
+        self.filter.category = category;
+        self.reload_filtered(repo);
+    }
+
+    fn handle_category_input(
+        &mut self,
+        action: Action,
+        repo: &mut dyn EntryRepository,
+    ) -> ScreenResult {
+        let Some(input) = self.category_input.as_mut() else {
+            return ScreenResult::None;
+        };
+        match action {
+            Action::Quit => return ScreenResult::Quit,
+            Action::Activate => {
+                // An empty category is rejected by `Category::new`, which
+                // conveniently means "no filter".
+                let category = Category::new(input.value().trim()).ok();
+                self.set_category_filter(category, repo);
+            }
+            Action::Cancel => self.set_category_filter(None, repo),
+            action => {
+                input.handle_action(action);
+            }
+        }
+        ScreenResult::None
+    }
+
+    fn handle_date_range(
+        &mut self,
+        action: Action,
+        repo: &mut dyn EntryRepository,
+    ) -> ScreenResult {
+        let Some(dialog) = self.date_range.as_mut() else {
+            return ScreenResult::None;
+        };
+        match action {
+            Action::Quit => return ScreenResult::Quit,
+            Action::Cancel => self.date_range = None,
+            Action::Activate => {
+                if let Some((from, to)) = dialog.parse() {
+                    self.date_range = None;
+                    self.filter.from = from;
+                    self.filter.to = to;
+                    self.reload_filtered(repo);
+                }
+            }
+            action => dialog.handle_action(action),
+        }
+        ScreenResult::None
+    }
+
+    /// Filters to the calendar month `offset` months from today (`0` is this
+    /// month); asking for the month already shown clears the date filter.
+    fn toggle_month(&mut self, offset: i32, repo: &dyn EntryRepository) {
+        let (first, last) = month_bounds(Local::now().date_naive(), offset);
+        if (self.filter.from, self.filter.to) == (Some(first), Some(last)) {
+            (self.filter.from, self.filter.to) = (None, None);
+        } else {
+            (self.filter.from, self.filter.to) = (Some(first), Some(last));
+        }
+        self.reload_filtered(repo);
+    }
+
+    fn clear_filters(&mut self, repo: &dyn EntryRepository) {
+        self.filter = EntryFilter::default();
+        self.reload_filtered(repo);
+    }
+
+    fn reload_filtered(&mut self, repo: &dyn EntryRepository) {
+        self.list_state.select(None);
+        if let Err(e) = self.refresh_entries(repo) {
+            log::error!("loading entries failed: {e}");
+            self.error_message = Some(user_message(&e));
+        }
+    }
+
+    fn is_filtered(&self) -> bool {
+        self.filter.category.is_some()
+            || self.filter.from.is_some()
+            || self.filter.to.is_some()
+            || self.filter.only_uncleared
+    }
+
+    fn list_title(&self) -> String {
+        let mut title = "Entries".to_string();
+        if let Some(category) = &self.filter.category {
+            title.push_str(&format!(" [category: {}]", category.as_str()));
+        }
+        if let (Some(from), Some(to)) = (self.filter.from, self.filter.to)
+            && month_bounds(from, 0) == (from, to)
+        {
+            title.push_str(&format!(" [{}]", from.format("%B %Y")));
+        } else if self.filter.from.is_some() || self.filter.to.is_some() {
+            let bound = |date: Option<NaiveDate>| {
+                date.map_or("...".to_string(), |d| d.format(DATE_FORMAT).to_string())
+            };
+            title.push_str(&format!(
+                " [{} to {}]",
+                bound(self.filter.from),
+                bound(self.filter.to)
+            ));
+        }
+        if self.filter.only_uncleared {
+            title.push_str(" [uncleared]");
+        }
+        title
+    }
+
+    #[cfg(test)]
+    pub(crate) fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub(crate) fn selected_entry(&self) -> Option<&Entry> {
+        self.list_state.selected().and_then(|i| self.entries.get(i))
+    }
+
+    fn delete_selected(&mut self, repo: &mut dyn EntryRepository) {
+        self.confirm_delete = false;
+        let Some(id) = self.selected_entry().map(|entry| entry.id) else {
+            return;
+        };
+        let result = repo.delete_entry(id).and_then(|()| {
+            self.last_deleted = Some(id);
+            self.refresh_entries(repo)
+        });
+        if let Err(e) = result {
+            log::error!("deleting entry failed: {e}");
+            self.error_message = Some(user_message(&e));
+        }
+    }
+
+    /// Un-archives the last deleted entry and selects it again.
+    fn undo_delete(&mut self, repo: &mut dyn EntryRepository) -> ScreenResult {
+        let Some(id) = self.last_deleted.take() else {
+            return ScreenResult::None;
+        };
+        let result = repo
+            .restore_entry(id)
+            .and_then(|()| self.reload_and_select(id, repo));
+        match result {
+            Ok(()) => ScreenResult::Toast("Restored entry".to_string(), ToastLevel::Success),
+            Err(e) => ScreenResult::Error(e),
+        }
+    }
+
+    /// Adds a copy of the selected entry dated today and selects the copy.
+    fn duplicate_selected(&mut self, repo: &mut dyn EntryRepository) -> ScreenResult {
+        let Some(entry) = self.selected_entry().cloned() else {
+            return ScreenResult::None;
+        };
+        let copy = NewEntry {
+            kind: entry.kind,
+            amount: entry.amount,
+            category: entry.category,
+            note: entry.note,
+            occurred_on: Local::now().date_naive(),
+            account: entry.account,
+            tags: entry.tags,
+        };
+        let result = repo
+            .add(copy)
+            .and_then(|added| self.reload_and_select(added.id, repo));
+        match result {
+            Ok(()) => ScreenResult::Toast("Duplicated entry".to_string(), ToastLevel::Success),
+            Err(e) => ScreenResult::Error(e),
+        }
+    }
+
+    /// Puts a one-line summary of the highlighted entry on the clipboard.
+    fn yank_selected(&mut self) -> ScreenResult {
+        let Some(summary) = self.selected_entry().map(entry_summary) else {
+            return ScreenResult::None;
+        };
+        match self.clipboard.copy(&summary) {
+            Ok(()) => ScreenResult::Toast("Copied to clipboard".to_string(), ToastLevel::Success),
+            Err(err) => ScreenResult::Toast(format!("Cannot copy: {err}"), ToastLevel::Error),
+        }
+    }
+
+    /// Flips the reconciled mark of the highlighted entry.
+    fn toggle_cleared(&mut self, repo: &mut dyn EntryRepository) -> ScreenResult {
+        let Some(entry) = self.selected_entry() else {
+            return ScreenResult::None;
+        };
+        let (id, cleared) = (entry.id, !entry.cleared);
+        match repo
+            .set_cleared(id, cleared)
+            .and_then(|()| self.reload_and_select(id, repo))
+        {
+            Ok(()) => ScreenResult::None,
+            Err(e) => ScreenResult::Error(e),
+        }
+    }
+
+    /// Reloads the list and moves the selection to `id` if it is listed.
+    fn reload_and_select(
+        &mut self,
+        id: EntryId,
+        repo: &dyn EntryRepository,
+    ) -> Result<(), DomainError> {
+        self.refresh_entries(repo)?;
+        if let Some(index) = self.entries.iter().position(|e| e.id == id) {
+            self.list_state.select(Some(index));
         }
         Ok(())
     }
+
+    fn handle_confirm_delete(
+        &mut self,
+        action: Action,
+        repo: &mut dyn EntryRepository,
+    ) -> ScreenResult {
+        match action {
+            Action::Quit => return ScreenResult::Quit,
+            Action::InputChar('y') => self.delete_selected(repo),
+            Action::InputChar('n') | Action::Cancel => self.confirm_delete = false,
+            _ => {}
+        }
+        ScreenResult::None
+    }
+
+    fn totals_line(&self, theme: &Theme) -> Line<'static> {
+        let money = |cents: i64| format_amount(&Money::from_minor(cents, self.currency));
+        let net = self.totals.net_cents();
+
+        Line::from(vec![
+            Span::raw(" Income: "),
+            Span::styled(money(self.totals.income_cents), theme.income),
+            Span::raw("  Expense: "),
+            Span::styled(
+                money(if self.signed_amounts {
+                    -self.totals.expense_cents.abs()
+                } else {
+                    self.totals.expense_cents
+                }),
+                theme.expense,
+            ),
+            Span::raw("  Net: "),
+            Span::styled(
+                money(net),
+                theme.amount(net < 0).add_modifier(Modifier::BOLD),
+            ),
+        ])
+    }
+
+    /// Draws the recent daily spending at the right of the header. On narrow
+    /// terminals only the most recent days that fit are shown.
+    fn render_sparkline(&self, frame: &mut ratatui::Frame<'_>, area: Rect, theme: &Theme) {
+        const LABEL: &str = "last 7 days ";
+        let [_, label_area, chart_area] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(LABEL.len() as u16),
+                Constraint::Length(SPARKLINE_WIDTH),
+            ])
+            .areas(area);
+        frame.render_widget(Paragraph::new(LABEL), label_area);
+
+        // Each day gets an equal share of the width; the newest days win
+        // when there is not room for all of them.
+        let per_day = (chart_area.width as usize / self.recent_spending.len().max(1)).max(1);
+        let fits = (chart_area.width as usize / per_day).min(self.recent_spending.len());
+        let data: Vec<u64> = self.recent_spending[self.recent_spending.len() - fits..]
+            .iter()
+            .flat_map(|value| std::iter::repeat_n(*value, per_day))
+            .collect();
+        let sparkline = Sparkline::default().data(&data).style(theme.expense);
+        frame.render_widget(sparkline, chart_area);
+    }
+
+    fn render_confirm_delete(&self, frame: &mut ratatui::Frame<'_>) {
+        let area = centered_rect(frame.area(), 30, 3);
+        frame.render_widget(Clear, area);
+        let prompt = Paragraph::new("Delete this entry? [y/n]")
+            .block(Block::default().title("Confirm").borders(Borders::ALL))
+            .alignment(Alignment::Center);
+        frame.render_widget(prompt, area);
+    }
 }
 
 impl Screen for DashboardScreen {
@@ -38,33 +428,56 @@ impl Screen for DashboardScreen {
         self.refresh_entries(repo)
     }
 
-    fn render(&mut self, frame: &mut ratatui::Frame<'_>) {
+    fn captures_text(&self) -> bool {
+        self.category_input.is_some() || self.date_range.is_some()
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame<'_>, theme: &Theme) {
         let area = frame.area();
         let chunks = main_chunks(area);
 
         let header = Block::default().title("TUI Money").borders(Borders::ALL);
+        let header_inner = header.inner(chunks[0]);
         frame.render_widget(header, chunks[0]);
+        self.render_sparkline(frame, header_inner, theme);
+
+        let body = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(chunks[1]);
+        frame.render_widget(Paragraph::new(self.totals_line(theme)), body[1]);
 
         // Dashboard Content
         if self.entries.is_empty() {
-            let body = Paragraph::new("No entries found. Press 'r' to reload.")
-                .block(Block::default().title("Dashboard").borders(Borders::ALL))
+            let message = if self.is_filtered() {
+                "No entries match the filter. Press 'x' to clear it."
+            } else {
+                "No entries found. Press 'r' to reload."
+            };
+            let empty = Paragraph::new(message)
+                .block(
+                    Block::default()
+                        .title(self.list_title())
+                        .borders(Borders::ALL),
+                )
                 .alignment(Alignment::Center);
-            frame.render_widget(body, chunks[1]);
+            frame.render_widget(empty, body[0]);
         } else {
             let items: Vec<ListItem> = self
                 .entries
                 .iter()
-                .map(|entry| {
-                    let amount_style = if entry.amount.is_negative() {
-                        Style::default().fg(Color::Red)
-                    } else {
-                        Style::default().fg(Color::Green)
-                    };
+                .zip(&self.balances)
+                .map(|(entry, &balance)| {
+                    let amount = self.shown_amount(entry);
+                    let amount_style = theme.amount(amount.is_negative());
 
                     let content = Line::from(vec![
+                        Span::styled(if entry.cleared { "✓ " } else { "  " }, theme.income),
                         Span::styled(
-                            format!("{:<12}", entry.occurred_on.format("%Y-%m-%d")),
+                            format!(
+                                "{:<12}",
+                                entry.occurred_on.format(&self.date_format).to_string()
+                            ),
                             Style::default(),
                         ),
                         Span::raw(" "),
@@ -73,30 +486,131 @@ impl Screen for DashboardScreen {
                             Style::default().add_modifier(Modifier::BOLD),
                         ),
                         Span::raw(" "),
-                        Span::styled(format!("{}", entry.amount), amount_style),
+                        Span::styled(format!("{:<14}", format_amount(&amount)), amount_style),
+                        Span::raw(" "),
+                        Span::styled(
+                            format!(
+                                "{:<14}",
+                                format_amount(&Money::from_minor(balance, entry.amount.currency()))
+                            ),
+                            theme.muted,
+                        ),
+                        Span::raw(" "),
+                        Span::raw(first_line(entry.note.as_deref().unwrap_or("")).to_string()),
                     ]);
                     ListItem::new(content)
                 })
                 .collect();
 
             let list = List::new(items)
-                .block(Block::default().title("Entries").borders(Borders::ALL))
+                .block(
+                    Block::default()
+                        .title(self.list_title())
+                        .borders(Borders::ALL),
+                )
                 .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
                 .highlight_symbol(">> ");
 
-            frame.render_stateful_widget(list, chunks[1], &mut self.list_state);
+            frame.render_stateful_widget(list, body[0], &mut self.list_state);
         }
 
-        let footer =
-            Paragraph::new("[q] quit  [r] reload").block(Block::default().borders(Borders::ALL));
-        frame.render_widget(footer, chunks[2]);
+        let footer = match (&self.category_input, &self.error_message) {
+            (Some(input), _) => {
+                let mut spans = vec![Span::styled(
+                    "Category: ",
+                    Style::default().add_modifier(Modifier::BOLD),
+                )];
+                spans.extend(input.lines(Style::default(), true)[0].spans.clone());
+                spans.push(Span::raw("  [Enter] apply  [Esc] clear"));
+                Paragraph::new(Line::from(spans))
+            }
+            (None, Some(err)) => Paragraph::new(err.as_str()).style(theme.error),
+            // Only the essentials, so the line fits 80 columns; `?` lists the rest.
+            (None, None) => Paragraph::new(
+                "[^Q] quit  [a] add  [d] delete  [space] cleared ✓  [x] reset filters  [?] help",
+            ),
+        };
+        frame.render_widget(
+            footer.block(Block::default().borders(Borders::ALL)),
+            chunks[2],
+        );
+
+        if self.confirm_delete {
+            self.render_confirm_delete(frame);
+        }
+        if let Some(dialog) = &self.date_range {
+            dialog.render(frame, theme);
+        }
     }
 
     fn handle_action(&mut self, action: Action, repo: &mut dyn EntryRepository) -> ScreenResult {
+        if self.confirm_delete {
+            return self.handle_confirm_delete(action, repo);
+        }
+        if self.category_input.is_some() {
+            return self.handle_category_input(action, repo);
+        }
+        if self.date_range.is_some() {
+            return self.handle_date_range(action, repo);
+        }
+
+        self.error_message = None;
         match action {
             Action::Quit => ScreenResult::Quit,
-            Action::InputChar('r') => {
-                let _ = self.refresh_entries(repo);
+            Action::Logout => ScreenResult::Logout,
+            Action::InputChar('r') => match self.refresh_entries(repo) {
+                Ok(()) => ScreenResult::None,
+                Err(e) => ScreenResult::Error(e),
+            },
+            Action::InputChar('a') => ScreenResult::Go(ScreenId::AddEntry),
+            Action::InputChar('s') => ScreenResult::Go(ScreenId::Stats),
+            Action::InputChar('/') => {
+                self.open_category_filter();
+                ScreenResult::None
+            }
+            Action::Cancel if self.filter.category.is_some() => {
+                self.set_category_filter(None, repo);
+                ScreenResult::None
+            }
+            Action::InputChar('f') => {
+                self.date_range = Some(DateRangeDialog::new(&self.filter));
+                ScreenResult::None
+            }
+            Action::InputChar('m') => {
+                self.toggle_month(0, repo);
+                ScreenResult::None
+            }
+            Action::InputChar('M') => {
+                self.toggle_month(-1, repo);
+                ScreenResult::None
+            }
+            Action::InputChar('x') => {
+                self.clear_filters(repo);
+                ScreenResult::None
+            }
+            Action::InputChar('d') => {
+                self.confirm_delete = self.selected_entry().is_some();
+                ScreenResult::None
+            }
+            Action::InputChar('u') => self.undo_delete(repo),
+            Action::InputChar('c') => self.duplicate_selected(repo),
+            Action::InputChar('y') => self.yank_selected(),
+            Action::InputChar(' ') => self.toggle_cleared(repo),
+            Action::InputChar('o') => {
+                self.filter.only_uncleared = !self.filter.only_uncleared;
+                self.reload_filtered(repo);
+                ScreenResult::None
+            }
+            Action::NavFirst => {
+                if !self.entries.is_empty() {
+                    self.list_state.select(Some(0));
+                }
+                ScreenResult::None
+            }
+            Action::NavLast => {
+                if !self.entries.is_empty() {
+                    self.list_state.select(Some(self.entries.len() - 1));
+                }
                 ScreenResult::None
             }
             Action::NavDown | Action::FocusNext => {
@@ -135,3 +649,531 @@ impl Screen for DashboardScreen {
         }
     }
 }
+
+/// The balance after each entry, in the order given. Entries are summed
+/// oldest first (by date, then id) so a newest-first list still shows the
+/// balance as it stood on each row; transfers leave it unchanged. Each
+/// currency keeps its own balance, in that currency's minor units.
+fn running_balances(entries: &[Entry]) -> Vec<i64> {
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by_key(|&i| (entries[i].occurred_on, entries[i].id.0));
+
+    let mut balances = vec![0; entries.len()];
+    let mut per_currency: HashMap<&str, i64> = HashMap::new();
+    for i in order {
+        let entry = &entries[i];
+        let cents = minor_units(&entry.amount)
+            .unwrap_or_default()
+            .saturating_abs();
+        let balance = per_currency
+            .entry(entry.amount.currency().iso_alpha_code)
+            .or_default();
+        *balance = match entry.kind {
+            EntryKind::Income => balance.saturating_add(cents),
+            EntryKind::Expense => balance.saturating_sub(cents),
+            EntryKind::Transfer => *balance,
+        };
+        balances[i] = *balance;
+    }
+    balances
+}
+
+/// Small modal for entering a `from`/`to` date range.
+struct DateRangeDialog {
+    from: TextInput,
+    to: TextInput,
+    focus_to: bool,
+    error_message: Option<String>,
+}
+
+impl DateRangeDialog {
+    fn new(filter: &EntryFilter) -> Self {
+        let mut from = TextInput::new();
+        let mut to = TextInput::new();
+        if let Some(date) = filter.from {
+            from.set_value(date.format(DATE_FORMAT).to_string());
+        }
+        if let Some(date) = filter.to {
+            to.set_value(date.format(DATE_FORMAT).to_string());
+        }
+        Self {
+            from,
+            to,
+            focus_to: false,
+            error_message: None,
+        }
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        let input = if self.focus_to {
+            &mut self.to
+        } else {
+            &mut self.from
+        };
+        match action {
+            Action::FocusNext | Action::FocusPrev | Action::NavUp | Action::NavDown => {
+                self.focus_to = !self.focus_to;
+            }
+            action => {
+                if input.handle_action(action) {
+                    self.error_message = None;
+                }
+            }
+        }
+    }
+
+    /// Parses both bounds; blank fields mean "open ended". On failure the
+    /// error is kept for display and `None` is returned.
+    fn parse(&mut self) -> Option<(Option<NaiveDate>, Option<NaiveDate>)> {
+        let parse_bound = |label: &str, input: &TextInput| {
+            let value = input.value().trim();
+            if value.is_empty() {
+                return Ok(None);
+            }
+            NaiveDate::parse_from_str(value, DATE_FORMAT)
+                .map(Some)
+                .map_err(|_| format!("{label} date must be YYYY-MM-DD"))
+        };
+
+        let result = parse_bound("From", &self.from).and_then(|from| {
+            let to = parse_bound("To", &self.to)?;
+            match (from, to) {
+                (Some(from), Some(to)) if from > to => {
+                    Err("From date must not be after To date".to_string())
+                }
+                _ => Ok((from, to)),
+            }
+        });
+        match result {
+            Ok(range) => Some(range),
+            Err(message) => {
+                self.error_message = Some(message);
+                None
+            }
+        }
+    }
+
+    fn render(&self, frame: &mut ratatui::Frame<'_>, theme: &Theme) {
+        let area = centered_rect(frame.area(), 40, 5);
+        frame.render_widget(Clear, area);
+        let block = Block::default().title("Date range").borders(Borders::ALL);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let field = |label: &'static str, input: &TextInput, focused: bool| {
+            let style = if focused {
+                theme.focus
+            } else {
+                Style::default()
+            };
+            let mut line = input.bracketed_line(style, focused);
+            line.spans.insert(
+                0,
+                Span::styled(label, Style::default().add_modifier(Modifier::BOLD)),
+            );
+            line
+        };
+
+        let mut lines = vec![
+            field("From: ", &self.from, !self.focus_to),
+            field("To:   ", &self.to, self.focus_to),
+        ];
+        match &self.error_message {
+            Some(err) => lines.push(Line::from(Span::styled(err.as_str(), theme.error))),
+            None => lines.push(Line::from("[Enter] apply  [Esc] cancel")),
+        }
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::{Category, NewEntry};
+    use rusty_money::{Money, iso};
+    use storage::SqliteRepository;
+
+    fn repo_with_entries(count: i64) -> SqliteRepository {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        for day in 1..=count {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: Money::from_minor(100 * day, iso::USD),
+                category: Category::new("food").unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, 1, day as u32).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+        repo
+    }
+
+    #[test]
+    fn delete_requires_confirmation() {
+        let mut repo = repo_with_entries(2);
+        let mut screen = DashboardScreen::new();
+        screen.init(&mut repo).expect("init");
+
+        screen.handle_action(Action::InputChar('d'), &mut repo);
+        assert!(screen.confirm_delete);
+        screen.handle_action(Action::NavDown, &mut repo);
+        assert_eq!(screen.list_state.selected(), Some(0));
+        screen.handle_action(Action::InputChar('n'), &mut repo);
+        assert!(!screen.confirm_delete);
+
+        screen.handle_action(Action::InputChar('d'), &mut repo);
+        screen.handle_action(Action::Cancel, &mut repo);
+        assert!(!screen.confirm_delete);
+        assert_eq!(repo.list(EntryFilter::default()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn deleting_last_row_keeps_selection_in_range() {
+        let mut repo = repo_with_entries(2);
+        let mut screen = DashboardScreen::new();
+        screen.init(&mut repo).expect("init");
+
+        screen.handle_action(Action::NavDown, &mut repo);
+        assert_eq!(screen.list_state.selected(), Some(1));
+        screen.handle_action(Action::InputChar('d'), &mut repo);
+        screen.handle_action(Action::InputChar('y'), &mut repo);
+
+        assert!(!screen.confirm_delete);
+        assert_eq!(screen.entries.len(), 1);
+        assert_eq!(screen.list_state.selected(), Some(0));
+
+        screen.handle_action(Action::InputChar('d'), &mut repo);
+        screen.handle_action(Action::InputChar('y'), &mut repo);
+        assert!(screen.entries.is_empty());
+        assert_eq!(screen.list_state.selected(), None);
+
+        screen.handle_action(Action::InputChar('d'), &mut repo);
+        assert!(!screen.confirm_delete);
+    }
+
+    #[test]
+    fn reload_keeps_the_selected_entry() {
+        let mut repo = repo_with_entries(4);
+        let mut screen = DashboardScreen::new();
+        screen.init(&mut repo).expect("init");
+
+        screen.handle_action(Action::NavDown, &mut repo);
+        screen.handle_action(Action::NavDown, &mut repo);
+        let selected = screen.selected_entry().expect("selection").id;
+
+        // Another process removes the newest entry above the selection.
+        let newest = screen.entries[0].id;
+        repo.delete_entry(newest).expect("entry deleted");
+        screen.handle_action(Action::InputChar('r'), &mut repo);
+        assert_eq!(screen.list_state.selected(), Some(1));
+        assert_eq!(
+            screen.selected_entry().map(|entry| entry.id),
+            Some(selected)
+        );
+
+        // The selected entry itself disappears: the index stays valid.
+        screen.handle_action(Action::NavLast, &mut repo);
+        repo.delete_entry(screen.selected_entry().expect("selection").id)
+            .expect("entry deleted");
+        screen.handle_action(Action::InputChar('r'), &mut repo);
+        assert_eq!(screen.entries.len(), 2);
+        assert_eq!(screen.list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn undo_restores_the_last_deleted_entry_once() {
+        let mut repo = repo_with_entries(2);
+        let mut screen = DashboardScreen::new();
+        screen.init(&mut repo).expect("init");
+        let deleted = screen.entries[0].clone();
+
+        screen.handle_action(Action::InputChar('d'), &mut repo);
+        screen.handle_action(Action::InputChar('y'), &mut repo);
+        assert_eq!(repo.list(EntryFilter::default()).unwrap().len(), 1);
+
+        let result = screen.handle_action(Action::InputChar('u'), &mut repo);
+        assert!(matches!(
+            result,
+            ScreenResult::Toast(message, ToastLevel::Success) if message == "Restored entry"
+        ));
+        let entries = repo.list(EntryFilter::default()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().any(|entry| entry.id == deleted.id));
+        assert_eq!(
+            screen.selected_entry().map(|entry| entry.id),
+            Some(deleted.id)
+        );
+
+        let result = screen.handle_action(Action::InputChar('u'), &mut repo);
+        assert!(matches!(result, ScreenResult::None));
+        assert_eq!(repo.list(EntryFilter::default()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn copy_adds_the_selected_entry_dated_today() {
+        let mut repo = repo_with_entries(2);
+        let mut screen = DashboardScreen::new();
+        screen.init(&mut repo).expect("init");
+        screen.handle_action(Action::NavDown, &mut repo);
+        let original = screen.selected_entry().expect("selection").clone();
+
+        let result = screen.handle_action(Action::InputChar('c'), &mut repo);
+        assert!(matches!(
+            result,
+            ScreenResult::Toast(message, ToastLevel::Success) if message == "Duplicated entry"
+        ));
+
+        let today = Local::now().date_naive();
+        let entries = repo.list(EntryFilter::default()).unwrap();
+        assert_eq!(entries.len(), 3);
+        let copy = screen.selected_entry().expect("selection");
+        assert_ne!(copy.id, original.id);
+        assert_eq!(copy.occurred_on, today);
+        assert_eq!(copy.amount, original.amount);
+        assert_eq!(copy.category, original.category);
+    }
+
+    #[test]
+    fn space_toggles_cleared_and_o_hides_cleared_entries() {
+        let mut repo = repo_with_entries(2);
+        let mut screen = DashboardScreen::new();
+        screen.init(&mut repo).expect("init");
+        screen.handle_action(Action::NavDown, &mut repo);
+        let id = screen.selected_entry().expect("selection").id;
+
+        screen.handle_action(Action::InputChar(' '), &mut repo);
+        assert!(repo.get_entry(id).unwrap().cleared);
+        assert_eq!(screen.selected_entry().map(|entry| entry.id), Some(id));
+
+        screen.handle_action(Action::InputChar('o'), &mut repo);
+        assert_eq!(screen.entries.len(), 1);
+        assert!(screen.list_title().ends_with("[uncleared]"));
+        screen.handle_action(Action::InputChar('o'), &mut repo);
+        assert_eq!(screen.entries.len(), 2);
+
+        screen
+            .list_state
+            .select(screen.entries.iter().position(|e| e.id == id));
+        screen.handle_action(Action::InputChar(' '), &mut repo);
+        assert!(!repo.get_entry(id).unwrap().cleared);
+    }
+
+    #[test]
+    fn category_filter_narrows_list_and_esc_clears_it() {
+        let mut repo = repo_with_entries(2);
+        repo.add(NewEntry {
+            kind: EntryKind::Expense,
+            amount: Money::from_minor(4000, iso::USD),
+            category: Category::new("rent").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 3).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+        let mut screen = DashboardScreen::new();
+        screen.init(&mut repo).expect("init");
+        assert_eq!(screen.entries.len(), 3);
+
+        screen.handle_action(Action::InputChar('/'), &mut repo);
+        for ch in "rent".chars() {
+            screen.handle_action(Action::InputChar(ch), &mut repo);
+        }
+        screen.handle_action(Action::Activate, &mut repo);
+        assert_eq!(screen.entries.len(), 1);
+        assert_eq!(screen.list_title(), "Entries [category: rent]");
+        assert_eq!(screen.totals.expense_cents, 4000);
+
+        screen.handle_action(Action::Cancel, &mut repo);
+        assert_eq!(screen.entries.len(), 3);
+        assert_eq!(screen.list_title(), "Entries");
+    }
+
+    #[test]
+    fn date_range_dialog_filters_and_rejects_bad_dates() {
+        let mut repo = repo_with_entries(3);
+        let mut screen = DashboardScreen::new();
+        screen.init(&mut repo).expect("init");
+
+        screen.handle_action(Action::InputChar('f'), &mut repo);
+        for ch in "2024-01-0x".chars() {
+            screen.handle_action(Action::InputChar(ch), &mut repo);
+        }
+        screen.handle_action(Action::Activate, &mut repo);
+        let dialog = screen.date_range.as_ref().expect("dialog stays open");
+        assert!(dialog.error_message.is_some());
+        assert_eq!(screen.entries.len(), 3);
+
+        screen.handle_action(Action::Backspace, &mut repo);
+        screen.handle_action(Action::InputChar('2'), &mut repo);
+        screen.handle_action(Action::FocusNext, &mut repo);
+        for ch in "2024-01-02".chars() {
+            screen.handle_action(Action::InputChar(ch), &mut repo);
+        }
+        screen.handle_action(Action::Activate, &mut repo);
+        assert!(screen.date_range.is_none());
+        assert_eq!(screen.entries.len(), 1);
+        assert_eq!(screen.list_title(), "Entries [2024-01-02 to 2024-01-02]");
+
+        screen.handle_action(Action::InputChar('x'), &mut repo);
+        assert_eq!(screen.entries.len(), 3);
+        assert_eq!(screen.list_title(), "Entries");
+    }
+
+    #[test]
+    fn first_and_last_jump_to_list_ends() {
+        let mut repo = repo_with_entries(3);
+        let mut screen = DashboardScreen::new();
+        screen.init(&mut repo).expect("init");
+
+        screen.handle_action(Action::NavLast, &mut repo);
+        assert_eq!(screen.list_state.selected(), Some(2));
+        screen.handle_action(Action::NavFirst, &mut repo);
+        assert_eq!(screen.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn totals_follow_refresh() {
+        let mut repo = repo_with_entries(2);
+        let mut screen = DashboardScreen::new();
+        screen.init(&mut repo).expect("init");
+        assert_eq!(screen.totals.expense_cents, 300);
+
+        repo.add(NewEntry {
+            kind: EntryKind::Income,
+            amount: Money::from_minor(1000, iso::USD),
+            category: Category::new("salary").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 1, 5).expect("date"),
+            account: None,
+            tags: Vec::new(),
+        })
+        .expect("entry added");
+        screen.handle_action(Action::InputChar('r'), &mut repo);
+        assert_eq!(screen.totals.income_cents, 1000);
+        assert_eq!(screen.totals.net_cents(), 700);
+    }
+
+    #[test]
+    fn renders_entries_from_the_repository() {
+        use domain::{EntryId, MockRepository};
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut repo = MockRepository::with_entries(vec![Entry {
+            id: EntryId(1),
+            kind: EntryKind::Expense,
+            amount: Money::from_minor(4250, iso::USD),
+            category: Category::new("groceries").unwrap(),
+            note: Some("weekly shop".to_string()),
+            occurred_on: NaiveDate::from_ymd_opt(2024, 3, 2).expect("date"),
+            account: None,
+            tags: Vec::new(),
+            cleared: false,
+            version: 1,
+        }]);
+        let mut screen = DashboardScreen::new();
+        screen.init(&mut repo).expect("init");
+        assert!(repo.was_called("list"));
+        assert!(repo.was_called("totals"));
+        assert!(!repo.was_called("delete_entry"));
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).expect("terminal");
+        terminal
+            .draw(|frame| screen.render(frame, &Theme::default()))
+            .expect("draw");
+        let buffer = terminal.backend().buffer();
+        let text: String = (0..buffer.area.height)
+            .flat_map(|y| (0..buffer.area.width).map(move |x| (x, y)))
+            .map(|pos| buffer[pos].symbol())
+            .collect();
+        assert!(text.contains("groceries"));
+        assert!(text.contains("$42.50"));
+    }
+
+    fn ledger_entry(id: i64, kind: EntryKind, cents: i64, day: u32) -> Entry {
+        Entry {
+            id: EntryId(id),
+            kind,
+            amount: Money::from_minor(cents, iso::USD),
+            category: Category::new("misc").unwrap(),
+            note: None,
+            occurred_on: NaiveDate::from_ymd_opt(2024, 1, day).expect("date"),
+            account: None,
+            tags: Vec::new(),
+            cleared: false,
+            version: 1,
+        }
+    }
+
+    #[test]
+    fn running_balance_walks_entries_oldest_first() {
+        // Listed newest first, as the repository returns them.
+        let entries = [
+            ledger_entry(4, EntryKind::Expense, 2500, 5),
+            ledger_entry(3, EntryKind::Transfer, 999, 4),
+            ledger_entry(2, EntryKind::Expense, 1000, 2),
+            ledger_entry(1, EntryKind::Income, 10000, 2),
+        ];
+        assert_eq!(running_balances(&entries), [6500, 9000, 9000, 10000]);
+
+        let income = [
+            ledger_entry(2, EntryKind::Income, 300, 2),
+            ledger_entry(1, EntryKind::Income, 200, 1),
+        ];
+        assert_eq!(running_balances(&income), [500, 200]);
+
+        let expenses = [
+            ledger_entry(2, EntryKind::Expense, 300, 2),
+            ledger_entry(1, EntryKind::Expense, 200, 1),
+        ];
+        assert_eq!(running_balances(&expenses), [-500, -200]);
+        assert!(running_balances(&[]).is_empty());
+    }
+
+    #[test]
+    fn running_balance_is_kept_per_currency() {
+        let mut yen = ledger_entry(2, EntryKind::Expense, 1500, 2);
+        yen.amount = Money::from_minor(1500, iso::JPY);
+        let mut euros = ledger_entry(4, EntryKind::Income, 700, 4);
+        euros.amount = Money::from_minor(700, iso::EUR);
+        let entries = [
+            euros,
+            ledger_entry(3, EntryKind::Expense, 250, 3),
+            yen,
+            ledger_entry(1, EntryKind::Income, 1000, 1),
+        ];
+        // USD: +1000 then -250; JPY and EUR start from zero on their own.
+        assert_eq!(running_balances(&entries), [700, 750, -1500, 1000]);
+    }
+
+    #[test]
+    fn m_toggles_the_current_month_filter() {
+        let mut repo = repo_with_entries(2);
+        let mut screen = DashboardScreen::new();
+        screen.init(&mut repo).expect("init");
+
+        screen.handle_action(Action::InputChar('m'), &mut repo);
+        let (first, last) = month_bounds(Local::now().date_naive(), 0);
+        assert_eq!(
+            (screen.filter.from, screen.filter.to),
+            (Some(first), Some(last))
+        );
+        assert!(
+            screen
+                .list_title()
+                .contains(&first.format("%B %Y").to_string())
+        );
+
+        screen.handle_action(Action::InputChar('M'), &mut repo);
+        assert_eq!(
+            screen.filter.from,
+            Some(month_bounds(Local::now().date_naive(), -1).0)
+        );
+
+        screen.handle_action(Action::InputChar('M'), &mut repo);
+        assert_eq!((screen.filter.from, screen.filter.to), (None, None));
+        assert_eq!(screen.list_title(), "Entries");
+    }
+}
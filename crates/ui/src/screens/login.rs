@@ -1,40 +1,54 @@
-use domain::EntryRepository;
+use domain::{DomainError, EntryRepository};
 use ratatui::layout::{Constraint, Direction, Layout};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 
-use super::{Screen, ScreenId, ScreenResult};
+use super::{Screen, ScreenId, ScreenResult, user_message};
 use crate::event::Action;
 use crate::layout::centered_rect;
+use crate::theme::Theme;
+use crate::widgets::TextInput;
+
+/// Users visible in the dropdown at once; the list scrolls past this.
+const DROPDOWN_ROWS: usize = 4;
 
 pub struct LoginScreen {
     focus: LoginFocus,
     user_options: Vec<String>,
+    /// Index into `filtered_users()`, not `user_options`.
     user_selected: usize,
+    /// First filtered user shown in the dropdown.
+    user_scroll: usize,
     user_dropdown_open: bool,
-    username_input: String,
-    password_input: String,
+    username_input: TextInput,
+    password_input: TextInput,
     error_message: Option<String>,
+    /// No accounts exist yet, so the screen steers towards creating one.
+    first_run: bool,
 }
 
+const FIRST_RUN_HINT: &str = "No users yet: choose Create User to add one.";
+
 impl LoginScreen {
     pub fn new() -> Self {
         Self {
             focus: LoginFocus::User,
             user_options: Vec::new(),
             user_selected: 0,
+            user_scroll: 0,
             user_dropdown_open: false,
-            username_input: String::new(),
-            password_input: String::new(),
+            username_input: TextInput::new(),
+            password_input: TextInput::masked(),
             error_message: None,
+            first_run: false,
         }
     }
 
+    /// Tab leaves the user field even while the dropdown is open (closing
+    /// it); only Up/Down are taken over by the dropdown.
     fn focus_next(&mut self) {
-        if self.user_dropdown_open {
-            return;
-        }
+        self.user_dropdown_open = false;
         self.focus = match self.focus {
             LoginFocus::User => LoginFocus::Password,
             LoginFocus::Password => LoginFocus::LoginButton,
@@ -44,9 +58,7 @@ impl LoginScreen {
     }
 
     fn focus_prev(&mut self) {
-        if self.user_dropdown_open {
-            return;
-        }
+        self.user_dropdown_open = false;
         self.focus = match self.focus {
             LoginFocus::User => LoginFocus::CreateUserButton,
             LoginFocus::Password => LoginFocus::User,
@@ -67,57 +79,100 @@ impl LoginScreen {
                         self.user_options = users;
                     }
                     self.user_dropdown_open = true;
+                    self.keep_selection_visible();
                 }
                 ScreenResult::None
             }
-            LoginFocus::CreateUserButton => {
-                if self.username_input.trim().is_empty() || self.password_input.is_empty() {
-                    self.error_message = Some("Username and password required".to_string());
-                    return ScreenResult::None;
-                }
-                match repo.create_user(&self.username_input, &self.password_input) {
-                    Ok(_) => {
-                        self.error_message = Some("User created! Log in now.".to_string());
-                        // Clear password to force re-entry or just login? Safe to generic message.
-                        self.password_input.clear();
-                    }
-                    Err(e) => {
-                        self.error_message = Some(format!("Error: {}", e));
-                    }
-                }
-                ScreenResult::None
-            }
-            LoginFocus::LoginButton => self.perform_login(repo),
-            LoginFocus::Password => self.perform_login(repo),
+            // The password has to be typed twice, which only that screen asks for.
+            LoginFocus::CreateUserButton => ScreenResult::Go(ScreenId::CreateUser),
+            LoginFocus::LoginButton | LoginFocus::Password => self.perform_login(repo),
         }
     }
 
     fn perform_login(&mut self, repo: &dyn EntryRepository) -> ScreenResult {
         // Using "GlobalEntryRepo" aliases just dyn EntryRepository for brevity in thought,
         // but here we use the trait directly.
-        if self.username_input.trim().is_empty() {
+        if self.username_input.value().trim().is_empty() {
             self.error_message = Some("Username required".to_string());
             return ScreenResult::None;
         }
-        match repo.verify_user(&self.username_input, &self.password_input) {
-            Ok(Some(_user)) => ScreenResult::Go(ScreenId::Dashboard),
+        match repo.verify_user(self.username_input.value(), self.password_input.value()) {
+            Ok(Some(user)) => {
+                log::info!("user {} logged in", user.username);
+                ScreenResult::LoggedIn(user)
+            }
             Ok(None) => {
+                // Never log the password, only who tried.
+                log::warn!("failed login for {}", self.username_input.value());
                 self.error_message = Some("Invalid credentials".to_string());
                 ScreenResult::None
             }
             Err(e) => {
-                self.error_message = Some(format!("Error: {}", e));
+                log::error!("login lookup failed: {e}");
+                self.error_message = Some(user_message(&e));
                 ScreenResult::None
             }
         }
     }
 
-    fn dropdown_lines(&self) -> Vec<Line<'_>> {
+    /// Fills in a just-created username and moves on to the password.
+    pub fn prefill_username(&mut self, username: &str) {
+        self.username_input.set_value(username);
+        self.focus = LoginFocus::Password;
+    }
+
+    /// No accounts existed when the screen was last opened.
+    pub fn is_first_run(&self) -> bool {
+        self.first_run
+    }
+
+    #[cfg(test)]
+    pub(crate) fn username(&self) -> &str {
+        self.username_input.value()
+    }
+
+    fn focused_input(&mut self) -> Option<&mut TextInput> {
+        match self.focus {
+            LoginFocus::User => Some(&mut self.username_input),
+            LoginFocus::Password => Some(&mut self.password_input),
+            _ => None,
+        }
+    }
+
+    /// Known users whose name contains the typed username, ignoring case.
+    fn filtered_users(&self) -> Vec<&str> {
+        let needle = self.username_input.value().trim().to_lowercase();
+        self.user_options
+            .iter()
+            .filter(|name| name.to_lowercase().contains(&needle))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Clamps the selection to the filtered list and scrolls the dropdown so
+    /// the selected user is on screen.
+    fn keep_selection_visible(&mut self) {
+        let count = self.filtered_users().len();
+        self.user_selected = self.user_selected.min(count.saturating_sub(1));
+        let max_scroll = count.saturating_sub(DROPDOWN_ROWS);
+        self.user_scroll = self
+            .user_scroll
+            .min(self.user_selected)
+            .max((self.user_selected + 1).saturating_sub(DROPDOWN_ROWS))
+            .min(max_scroll);
+    }
+
+    fn dropdown_lines(&self, theme: &Theme) -> Vec<Line<'_>> {
         let mut lines = Vec::new();
-        let max_items = self.user_options.len().min(4);
-        for (idx, name) in self.user_options.iter().enumerate().take(max_items) {
+        let visible = self
+            .filtered_users()
+            .into_iter()
+            .enumerate()
+            .skip(self.user_scroll)
+            .take(DROPDOWN_ROWS);
+        for (idx, name) in visible {
             let style = if idx == self.user_selected {
-                Style::default().fg(Color::Black).bg(Color::White)
+                theme.focus
             } else {
                 Style::default()
             };
@@ -128,12 +183,24 @@ impl LoginScreen {
 }
 
 impl Screen for LoginScreen {
-    fn render(&mut self, frame: &mut ratatui::Frame<'_>) {
+    fn init(&mut self, repo: &mut dyn EntryRepository) -> Result<(), DomainError> {
+        // Don't leave the previous user's input behind after a logout; this
+        // also masks a revealed password again.
+        *self = Self::new();
+        self.first_run = repo.list_users()?.is_empty();
+        Ok(())
+    }
+
+    fn captures_text(&self) -> bool {
+        matches!(self.focus, LoginFocus::User | LoginFocus::Password)
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame<'_>, theme: &Theme) {
         let area = frame.area();
         frame.render_widget(Clear, area);
 
         let dropdown_height = if self.user_dropdown_open {
-            self.user_options.len().min(4) as u16
+            self.filtered_users().len().min(DROPDOWN_ROWS) as u16
         } else {
             0
         };
@@ -144,7 +211,7 @@ impl Screen for LoginScreen {
         let block = Block::default()
             .title(" Login System ")
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan));
+            .border_style(theme.border);
         frame.render_widget(block.clone(), form_area);
 
         let inner_area = block.inner(form_area);
@@ -163,10 +230,7 @@ impl Screen for LoginScreen {
             .split(inner_area);
 
         // Styles
-        let focused_style = Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD);
-        let default_style = Style::default().fg(Color::Gray);
+        let (focused_style, default_style) = (theme.highlight, theme.inactive);
 
         let user_style = if self.focus == LoginFocus::User {
             focused_style
@@ -179,12 +243,12 @@ impl Screen for LoginScreen {
             default_style
         };
         let login_btn_style = if self.focus == LoginFocus::LoginButton {
-            focused_style.bg(Color::Blue).fg(Color::White)
+            theme.button
         } else {
             default_style
         };
         let create_btn_style = if self.focus == LoginFocus::CreateUserButton {
-            focused_style.bg(Color::Blue).fg(Color::White)
+            theme.button
         } else {
             default_style
         };
@@ -195,31 +259,29 @@ impl Screen for LoginScreen {
         } else {
             "▼"
         };
-        // Use username_input
-        let user_display = if self.username_input.is_empty() {
-            "Type or Select..."
+        let user_focused = self.focus == LoginFocus::User && !self.user_dropdown_open;
+        let mut user_spans = vec![Span::raw("Username: ")];
+        if self.username_input.value().is_empty() && !user_focused {
+            user_spans.push(Span::styled("Type or Select...", user_style));
         } else {
-            &self.username_input
-        };
-
-        let user_line = Line::from(vec![
-            Span::raw("Username: "),
-            Span::styled(format!("{} {}", user_display, user_arrow), user_style),
-        ]);
-        frame.render_widget(Paragraph::new(user_line), chunks[0]);
+            user_spans.extend(
+                self.username_input.lines(user_style, user_focused)[0]
+                    .spans
+                    .clone(),
+            );
+        }
+        user_spans.push(Span::styled(format!(" {}", user_arrow), user_style));
+        frame.render_widget(Paragraph::new(Line::from(user_spans)), chunks[0]);
 
         // 2. Password Field
-        let pass_stars = "*".repeat(self.password_input.len());
-        let pass_line = Line::from(vec![
-            Span::raw("Password: "),
-            Span::styled(pass_stars.to_string(), pass_style),
-            if (frame.count() / 30).is_multiple_of(2) {
-                Span::raw("_")
-            } else {
-                Span::raw(" ")
-            },
-        ]);
-        frame.render_widget(Paragraph::new(pass_line), chunks[2]);
+        let mut pass_spans = vec![Span::raw("Password: ")];
+        pass_spans.extend(
+            self.password_input
+                .lines(pass_style, self.focus == LoginFocus::Password)[0]
+                .spans
+                .clone(),
+        );
+        frame.render_widget(Paragraph::new(Line::from(pass_spans)), chunks[2]);
 
         // 3. Buttons
         let btns = Line::from(vec![
@@ -232,11 +294,15 @@ impl Screen for LoginScreen {
             chunks[4],
         );
 
-        // 4. Error Message
-        if let Some(err) = &self.error_message {
-            let err_line = Line::from(Span::styled(err, Style::default().fg(Color::Red)));
+        // 4. Error Message (or the first-run hint)
+        let message = match &self.error_message {
+            Some(err) => Some(Span::styled(err, theme.error)),
+            None if self.first_run => Some(Span::styled(FIRST_RUN_HINT, theme.info)),
+            None => None,
+        };
+        if let Some(message) = message {
             frame.render_widget(
-                Paragraph::new(err_line).alignment(ratatui::layout::Alignment::Center),
+                Paragraph::new(Line::from(message)).alignment(ratatui::layout::Alignment::Center),
                 chunks[6],
             );
         }
@@ -250,13 +316,11 @@ impl Screen for LoginScreen {
                 height: dropdown_height + 2,
             };
             frame.render_widget(Clear, dropdown_area);
-            let drop_block = Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().bg(Color::DarkGray));
+            let drop_block = Block::default().borders(Borders::ALL).style(theme.popup);
             let drop_inner = drop_block.inner(dropdown_area);
             frame.render_widget(drop_block, dropdown_area);
 
-            let items = self.dropdown_lines();
+            let items = self.dropdown_lines(theme);
             frame.render_widget(Paragraph::new(items), drop_inner);
         }
     }
@@ -264,9 +328,16 @@ impl Screen for LoginScreen {
     fn handle_action(&mut self, action: Action, repo: &mut dyn EntryRepository) -> ScreenResult {
         match action {
             Action::Quit => ScreenResult::Quit,
+            Action::ToggleReveal if self.focus == LoginFocus::Password => {
+                let revealed = !self.password_input.is_revealed();
+                self.password_input.set_revealed(revealed);
+                ScreenResult::None
+            }
             Action::Cancel => {
                 if self.user_dropdown_open {
                     self.user_dropdown_open = false;
+                } else {
+                    self.error_message = None;
                 }
                 ScreenResult::None
             }
@@ -279,22 +350,40 @@ impl Screen for LoginScreen {
                 ScreenResult::None
             }
             Action::NavUp => {
-                if self.user_dropdown_open && self.user_selected > 0 {
-                    self.user_selected -= 1;
+                if !self.user_dropdown_open {
+                    self.focus_prev();
+                } else {
+                    self.user_selected = self.user_selected.saturating_sub(1);
+                    self.keep_selection_visible();
                 }
                 ScreenResult::None
             }
             Action::NavDown => {
-                if self.user_dropdown_open && self.user_selected + 1 < self.user_options.len() {
+                if !self.user_dropdown_open {
+                    self.focus_next();
+                } else {
                     self.user_selected += 1;
+                    self.keep_selection_visible();
                 }
                 ScreenResult::None
             }
+            Action::NavLeft | Action::NavRight
+                if matches!(
+                    self.focus,
+                    LoginFocus::LoginButton | LoginFocus::CreateUserButton
+                ) =>
+            {
+                self.focus = match self.focus {
+                    LoginFocus::LoginButton => LoginFocus::CreateUserButton,
+                    _ => LoginFocus::LoginButton,
+                };
+                ScreenResult::None
+            }
             Action::Activate => {
                 if self.user_dropdown_open {
                     // Selection confirmed
-                    if let Some(name) = self.user_options.get(self.user_selected) {
-                        self.username_input = name.clone();
+                    if let Some(name) = self.filtered_users().get(self.user_selected) {
+                        self.username_input.set_value(name.to_string());
                     }
                     self.user_dropdown_open = false;
                     ScreenResult::None
@@ -302,22 +391,24 @@ impl Screen for LoginScreen {
                     self.activate(repo)
                 }
             }
-            Action::InputChar(ch) => {
-                self.error_message = None;
-                if self.focus == LoginFocus::User {
-                    self.username_input.push(ch);
-                } else if self.focus == LoginFocus::Password {
-                    self.password_input.push(ch);
+            Action::InputChar(_)
+            | Action::Paste(_)
+            | Action::Backspace
+            | Action::NavLeft
+            | Action::NavRight
+            | Action::LineStart
+            | Action::LineEnd => {
+                if matches!(
+                    action,
+                    Action::InputChar(_) | Action::Paste(_) | Action::Backspace
+                ) {
+                    self.error_message = None;
                 }
-                ScreenResult::None
-            }
-            Action::Backspace => {
-                self.error_message = None;
-                if self.focus == LoginFocus::User {
-                    self.username_input.pop();
-                } else if self.focus == LoginFocus::Password {
-                    self.password_input.pop();
+                if let Some(input) = self.focused_input() {
+                    input.handle_action(action);
                 }
+                // Typing in the username narrows the dropdown.
+                self.keep_selection_visible();
                 ScreenResult::None
             }
             _ => ScreenResult::None,
@@ -331,3 +422,149 @@ enum LoginFocus {
     LoginButton,
     CreateUserButton,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::UserRepository;
+    use storage::SqliteRepository;
+
+    #[test]
+    fn focus_cycles_and_tab_leaves_open_dropdown() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.create_user("alice", "secret-42")
+            .expect("user created");
+        repo.create_user("bob", "secret-42").expect("user created");
+        let mut screen = LoginScreen::new();
+
+        let order = [
+            LoginFocus::Password,
+            LoginFocus::LoginButton,
+            LoginFocus::CreateUserButton,
+            LoginFocus::User,
+        ];
+        for expected in order {
+            screen.handle_action(Action::FocusNext, &mut repo);
+            assert_eq!(screen.focus, expected);
+        }
+        for expected in order.iter().rev().skip(1) {
+            screen.handle_action(Action::FocusPrev, &mut repo);
+            assert_eq!(screen.focus, *expected);
+        }
+        screen.handle_action(Action::FocusPrev, &mut repo);
+        assert_eq!(screen.focus, LoginFocus::User);
+
+        // Up/Down drive the open dropdown instead of moving focus.
+        screen.handle_action(Action::Activate, &mut repo);
+        screen.handle_action(Action::NavDown, &mut repo);
+        assert_eq!(screen.focus, LoginFocus::User);
+        assert_eq!(screen.user_selected, 1);
+
+        screen.handle_action(Action::FocusNext, &mut repo);
+        assert!(!screen.user_dropdown_open);
+        assert_eq!(screen.focus, LoginFocus::Password);
+
+        screen.handle_action(Action::FocusPrev, &mut repo);
+        screen.handle_action(Action::Activate, &mut repo);
+        assert!(screen.user_dropdown_open);
+        screen.handle_action(Action::FocusPrev, &mut repo);
+        assert!(!screen.user_dropdown_open);
+        assert_eq!(screen.focus, LoginFocus::CreateUserButton);
+        screen.handle_action(Action::NavLeft, &mut repo);
+        assert_eq!(screen.focus, LoginFocus::LoginButton);
+    }
+
+    #[test]
+    fn cancel_closes_dropdown_before_clearing_error() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        repo.create_user("alice", "secret-42")
+            .expect("user created");
+        let mut screen = LoginScreen::new();
+
+        screen.handle_action(Action::Activate, &mut repo);
+        assert!(screen.user_dropdown_open);
+        screen.error_message = Some("Invalid credentials".to_string());
+
+        screen.handle_action(Action::Cancel, &mut repo);
+        assert!(!screen.user_dropdown_open);
+        assert!(screen.error_message.is_some());
+
+        screen.handle_action(Action::Cancel, &mut repo);
+        assert!(screen.error_message.is_none());
+    }
+
+    #[test]
+    fn dropdown_filters_and_scrolls_users() {
+        let mut repo = domain::MockRepository::new();
+        let names = [
+            "andrew", "anna", "bella", "ben", "carla", "dan", "dana", "eve", "frank", "hannah",
+        ];
+        for name in names {
+            repo.create_user(name, "secret-42").expect("user created");
+        }
+        let mut screen = LoginScreen::new();
+        screen.handle_action(Action::Activate, &mut repo);
+        assert_eq!(screen.filtered_users().len(), 10);
+
+        for _ in 0..5 {
+            screen.handle_action(Action::NavDown, &mut repo);
+        }
+        assert_eq!((screen.user_selected, screen.user_scroll), (5, 2));
+        for _ in 0..10 {
+            screen.handle_action(Action::NavDown, &mut repo);
+        }
+        assert_eq!((screen.user_selected, screen.user_scroll), (9, 6));
+        screen.handle_action(Action::NavUp, &mut repo);
+        assert_eq!((screen.user_selected, screen.user_scroll), (8, 6));
+
+        for ch in "AN".chars() {
+            screen.handle_action(Action::InputChar(ch), &mut repo);
+        }
+        assert_eq!(
+            screen.filtered_users(),
+            ["andrew", "anna", "dan", "dana", "frank", "hannah"]
+        );
+        assert_eq!((screen.user_selected, screen.user_scroll), (5, 2));
+
+        screen.handle_action(Action::InputChar('n'), &mut repo);
+        assert_eq!(screen.filtered_users(), ["anna", "hannah"]);
+        assert_eq!(screen.user_selected, 1);
+        screen.handle_action(Action::Activate, &mut repo);
+        assert_eq!(screen.username(), "hannah");
+
+        screen.username_input.set_value("zoe");
+        screen.handle_action(Action::Activate, &mut repo);
+        screen.handle_action(Action::NavDown, &mut repo);
+        assert!(screen.filtered_users().is_empty());
+        assert_eq!((screen.user_selected, screen.user_scroll), (0, 0));
+    }
+
+    #[test]
+    fn empty_database_points_to_creating_a_user() {
+        let mut repo = domain::MockRepository::new();
+        let mut screen = LoginScreen::new();
+        screen.init(&mut repo).expect("init");
+        assert!(screen.is_first_run());
+
+        // A single unconfirmed password never creates an account.
+        for ch in "alice".chars() {
+            screen.handle_action(Action::InputChar(ch), &mut repo);
+        }
+        screen.handle_action(Action::FocusNext, &mut repo);
+        for ch in "secret-42".chars() {
+            screen.handle_action(Action::InputChar(ch), &mut repo);
+        }
+        let result = screen.handle_action(Action::Activate, &mut repo);
+        assert!(matches!(result, ScreenResult::None));
+        assert!(repo.list_users().unwrap().is_empty());
+
+        screen.focus = LoginFocus::CreateUserButton;
+        let result = screen.handle_action(Action::Activate, &mut repo);
+        assert!(matches!(result, ScreenResult::Go(ScreenId::CreateUser)));
+
+        repo.create_user("alice", "secret-42")
+            .expect("user created");
+        screen.init(&mut repo).expect("init");
+        assert!(!screen.is_first_run());
+    }
+}
@@ -1,32 +1,107 @@
+mod add_entry;
 mod create_user;
 mod dashboard;
 mod login;
+#[cfg(test)]
+mod render_tests;
+mod stats;
 
+pub use add_entry::AddEntryScreen;
 pub use create_user::CreateUserScreen;
 pub use dashboard::DashboardScreen;
 pub use login::LoginScreen;
+pub use stats::StatsScreen;
 
 use crate::event::Action;
-use domain::EntryRepository;
+use crate::theme::Theme;
+use crate::toast::ToastLevel;
+use domain::{DomainError, EntryId, EntryRepository, User};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ScreenId {
     Dashboard,
     Login,
-    #[allow(dead_code)]
     CreateUser,
+    AddEntry,
+    Stats,
 }
 
 pub enum ScreenResult {
     None,
     Quit,
+    /// Open a screen on top of the current one; `Login` and `Dashboard` are
+    /// roots and replace the whole stack instead.
     Go(ScreenId),
+    /// Return to the screen this one was opened from.
+    Back,
+    /// Go back after saving an entry; the dashboard selects it once reloaded.
+    BackToEntry(EntryId),
+    /// An account was created; `App` returns to the login screen with the
+    /// username filled in.
+    UserCreated(String),
+    /// Credentials were accepted; `App` remembers the user and opens the dashboard.
+    LoggedIn(User),
+    /// Forget the current user and return to the login screen.
+    Logout,
+    /// Show a transient message at the bottom of the screen.
+    Toast(String, ToastLevel),
+    /// A repository call failed; `App` decides how to show it.
+    Error(DomainError),
 }
 
+/// A full-screen view driven by `App`.
+///
+/// `Action::Cancel` (Esc) undoes the innermost thing first: `App` closes its
+/// help and quit overlays before a screen sees the action, and a screen
+/// closes its own dialogs and dropdowns (or clears its error) before it
+/// navigates back.
 pub trait Screen {
-    fn init(&mut self, _repo: &mut dyn EntryRepository) -> Result<(), domain::DomainError> {
+    fn init(&mut self, _repo: &mut dyn EntryRepository) -> Result<(), DomainError> {
         Ok(())
     }
-    fn render(&mut self, frame: &mut ratatui::Frame<'_>);
+    /// Whether a text field has focus, so printable keys (like `?`) must be
+    /// typed into it rather than treated as shortcuts.
+    fn captures_text(&self) -> bool {
+        false
+    }
+    fn render(&mut self, frame: &mut ratatui::Frame<'_>, theme: &Theme);
     fn handle_action(&mut self, action: Action, repo: &mut dyn EntryRepository) -> ScreenResult;
 }
+
+/// Turns a repository error into text for the user. Validation and conflict
+/// messages are written for users and shown verbatim; storage details are
+/// internal, so the user gets a generic message. Logging the details is up
+/// to the caller, which knows what it was doing.
+pub(crate) fn user_message(err: &DomainError) -> String {
+    match err {
+        DomainError::InvalidData(message) | DomainError::Conflict(message) => message.clone(),
+        DomainError::NotFound => "Not found; it may have been deleted.".to_string(),
+        DomainError::Storage(_) => "Something went wrong saving your data.".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_error_variant_has_a_user_message() {
+        let message = |err| user_message(&err);
+        assert_eq!(
+            message(DomainError::InvalidData("Amount is required".to_string())),
+            "Amount is required"
+        );
+        assert_eq!(
+            message(DomainError::Conflict("username already taken".to_string())),
+            "username already taken"
+        );
+        assert_eq!(
+            message(DomainError::NotFound),
+            "Not found; it may have been deleted."
+        );
+        assert_eq!(
+            message(DomainError::Storage("disk I/O error".to_string())),
+            "Something went wrong saving your data."
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Renders each screen into a fixed-size `TestBackend` so layout regressions
+//! show up as test failures.
+
+use chrono::NaiveDate;
+use domain::{Category, Entry, EntryId, EntryKind, MockRepository, UserRepository};
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+use ratatui::{Terminal, backend::TestBackend};
+use rusty_money::{Money, iso};
+
+use super::{CreateUserScreen, DashboardScreen, LoginScreen, Screen};
+use crate::theme::Theme;
+
+const WIDTH: u16 = 80;
+const HEIGHT: u16 = 24;
+
+/// Draws `screen` once with `theme` and returns the whole buffer.
+fn render_buffer(screen: &mut dyn Screen, theme: &Theme) -> Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).expect("terminal");
+    terminal
+        .draw(|frame| screen.render(frame, theme))
+        .expect("draw");
+    terminal.backend().buffer().clone()
+}
+
+/// Draws `screen` once and returns the buffer as one string per row, with
+/// trailing blanks trimmed.
+fn render_rows(screen: &mut dyn Screen) -> Vec<String> {
+    let buffer = render_buffer(screen, &Theme::default());
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+/// Index of the first row containing `needle`, failing with the whole
+/// screen when it is missing.
+fn row_of(rows: &[String], needle: &str) -> usize {
+    rows.iter()
+        .position(|row| row.contains(needle))
+        .unwrap_or_else(|| panic!("{needle:?} not rendered:\n{}", rows.join("\n")))
+}
+
+fn entry(id: i64, kind: EntryKind, cents: i64, category: &str, day: u32) -> Entry {
+    Entry {
+        id: EntryId(id),
+        kind,
+        amount: Money::from_minor(cents, iso::USD),
+        category: Category::new(category).unwrap(),
+        note: None,
+        occurred_on: NaiveDate::from_ymd_opt(2024, 3, day).expect("date"),
+        account: None,
+        tags: Vec::new(),
+        cleared: false,
+        version: 1,
+    }
+}
+
+#[test]
+fn login_screen_shows_fields_and_buttons() {
+    let mut repo = MockRepository::new();
+    repo.create_user("alice", "secret-42")
+        .expect("user created");
+    let mut screen = LoginScreen::new();
+    screen.init(&mut repo).expect("init");
+
+    let rows = render_rows(&mut screen);
+    let title = row_of(&rows, "Login System");
+    let username = row_of(&rows, "Username:");
+    let password = row_of(&rows, "Password:");
+    let buttons = row_of(&rows, "[ Login ]");
+    assert!(title < username && username < password && password < buttons);
+    assert!(rows[buttons].contains("[ Create User ]"));
+}
+
+#[test]
+fn login_screen_hints_at_creating_the_first_user() {
+    let mut repo = MockRepository::new();
+    let mut screen = LoginScreen::new();
+    screen.init(&mut repo).expect("init");
+
+    let rows = render_rows(&mut screen);
+    assert!(row_of(&rows, "No users yet") > row_of(&rows, "[ Create User ]"));
+}
+
+#[test]
+fn create_user_screen_shows_fields_and_buttons() {
+    let mut repo = MockRepository::new();
+    let mut screen = CreateUserScreen::new();
+    screen.init(&mut repo).expect("init");
+
+    let rows = render_rows(&mut screen);
+    let title = row_of(&rows, "Create New User");
+    let fields = ["Login", "Password", "Repeat"].map(|label| row_of(&rows[title + 1..], label));
+    assert_eq!(fields, [0, 1, 2]);
+    let buttons = row_of(&rows, " Create ");
+    assert!(rows[buttons].contains("Back"));
+}
+
+#[test]
+fn empty_dashboard_shows_placeholder_and_key_hints() {
+    let mut repo = MockRepository::new();
+    let mut screen = DashboardScreen::new();
+    screen.init(&mut repo).expect("init");
+
+    let rows = render_rows(&mut screen);
+    assert_eq!(row_of(&rows, "TUI Money"), 0);
+    row_of(&rows, "No entries found. Press 'r' to reload.");
+    row_of(&rows, "Income: $0.00  Expense: $0.00  Net: $0.00");
+    let footer = row_of(&rows, "[^Q] quit  [a] add");
+    assert_eq!(footer, usize::from(HEIGHT) - 2);
+    assert!(rows[footer].contains("[x] reset filters  [?] help"));
+}
+
+#[test]
+fn dashboard_lists_entries_and_totals() {
+    let mut repo = MockRepository::with_entries(vec![
+        entry(1, EntryKind::Income, 250000, "salary", 1),
+        entry(2, EntryKind::Expense, 4250, "groceries", 2),
+    ]);
+    let mut screen = DashboardScreen::new();
+    screen.init(&mut repo).expect("init");
+
+    let rows = render_rows(&mut screen);
+    let list = row_of(&rows, "Entries");
+    let groceries = row_of(&rows, "groceries");
+    let salary = row_of(&rows, "salary");
+    assert!(list < groceries && groceries < salary);
+    assert!(rows[groceries].contains(">> "));
+    assert!(rows[groceries].contains("$42.50"));
+    row_of(&rows, "Net: $2,457.50");
+    assert!(rows.iter().all(|row| !row.contains("No entries found")));
+}
+
+#[test]
+fn dashboard_shows_each_entrys_own_currency() {
+    let mut euros = entry(2, EntryKind::Expense, 1250, "museum", 3);
+    euros.amount = Money::from_minor(1250, iso::EUR);
+    let mut repo = MockRepository::with_entries(vec![
+        entry(1, EntryKind::Expense, 4250, "groceries", 2),
+        euros,
+    ]);
+    let mut screen = DashboardScreen::new();
+    screen.init(&mut repo).expect("init");
+
+    let rows = render_rows(&mut screen);
+    assert!(rows[row_of(&rows, "groceries")].contains("$42.50"));
+    assert!(rows[row_of(&rows, "museum")].contains("€12,50"));
+}
+
+#[test]
+fn monochrome_dashboard_uses_no_red_or_green() {
+    let mut repo = MockRepository::with_entries(vec![
+        entry(1, EntryKind::Income, 250000, "salary", 1),
+        entry(2, EntryKind::Expense, 4250, "groceries", 2),
+    ]);
+    let mut screen = DashboardScreen::new();
+    screen.init(&mut repo).expect("init");
+
+    let mut colors = |theme: &Theme| -> Vec<Color> {
+        let buffer = render_buffer(&mut screen, theme);
+        buffer
+            .content()
+            .iter()
+            .flat_map(|cell| [cell.fg, cell.bg])
+            .collect()
+    };
+    let default = colors(&Theme::default());
+    assert!(default.contains(&Color::Red) && default.contains(&Color::Green));
+
+    let monochrome = colors(&Theme::monochrome());
+    assert!(
+        !monochrome
+            .iter()
+            .any(|color| matches!(color, Color::Red | Color::Green))
+    );
+}
@@ -0,0 +1,169 @@
+use chrono::{Local, NaiveDate};
+use domain::{
+    CategoryTotal, DEFAULT_CURRENCY, DomainError, EntryFilter, EntryKind, EntryRepository,
+    month_bounds,
+};
+use ratatui::layout::{Alignment, Direction};
+use ratatui::text::Line;
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Borders, Paragraph};
+use rusty_money::{Money, iso};
+
+use super::{Screen, ScreenResult, user_message};
+use crate::event::Action;
+use crate::format::format_amount;
+use crate::layout::main_chunks;
+use crate::theme::Theme;
+
+/// Expense totals per category for the current month, drawn as a bar chart.
+pub struct StatsScreen {
+    /// First day of the month being shown.
+    month: NaiveDate,
+    breakdown: Vec<CategoryTotal>,
+    currency: &'static iso::Currency,
+    error_message: Option<String>,
+}
+
+impl StatsScreen {
+    pub fn new() -> Self {
+        Self {
+            month: month_bounds(Local::now().date_naive(), 0).0,
+            breakdown: Vec::new(),
+            currency: DEFAULT_CURRENCY,
+            error_message: None,
+        }
+    }
+
+    fn refresh(&mut self, repo: &dyn EntryRepository) -> Result<(), DomainError> {
+        let (first_day, last_day) = month_bounds(self.month, 0);
+        self.breakdown = repo.category_breakdown(EntryFilter {
+            from: Some(first_day),
+            to: Some(last_day),
+            kind: Some(EntryKind::Expense),
+            ..EntryFilter::default()
+        })?;
+        self.currency = repo.default_currency();
+        Ok(())
+    }
+
+    fn reload(&mut self, repo: &dyn EntryRepository) {
+        self.error_message = self.refresh(repo).err().map(|e| {
+            log::error!("loading stats failed: {e}");
+            user_message(&e)
+        });
+    }
+}
+
+impl Screen for StatsScreen {
+    fn init(&mut self, repo: &mut dyn EntryRepository) -> Result<(), DomainError> {
+        self.month = month_bounds(Local::now().date_naive(), 0).0;
+        self.refresh(repo)
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame<'_>, theme: &Theme) {
+        let chunks = main_chunks(frame.area());
+
+        let header = Block::default().title("TUI Money").borders(Borders::ALL);
+        frame.render_widget(header, chunks[0]);
+
+        let block = Block::default()
+            .title(format!(
+                "Spending by category - {}",
+                self.month.format("%B %Y")
+            ))
+            .borders(Borders::ALL);
+        if self.breakdown.is_empty() {
+            let empty = Paragraph::new("No expenses recorded this month.")
+                .block(block)
+                .alignment(Alignment::Center);
+            frame.render_widget(empty, chunks[1]);
+        } else {
+            let bars: Vec<Bar> = self
+                .breakdown
+                .iter()
+                .map(|row| {
+                    Bar::default()
+                        .label(Line::from(row.category.as_str().to_string()))
+                        .value(row.total_cents.max(0) as u64)
+                        .text_value(format_amount(&Money::from_minor(
+                            row.total_cents,
+                            self.currency,
+                        )))
+                })
+                .collect();
+            let max = self
+                .breakdown
+                .iter()
+                .map(|row| row.total_cents.max(0) as u64)
+                .max()
+                .unwrap_or(0);
+            let chart = BarChart::default()
+                .block(block)
+                .direction(Direction::Horizontal)
+                .bar_width(1)
+                .bar_gap(0)
+                .bar_style(theme.expense)
+                .max(max)
+                .data(BarGroup::default().bars(&bars));
+            frame.render_widget(chart, chunks[1]);
+        }
+
+        let footer = match &self.error_message {
+            Some(err) => Paragraph::new(err.as_str()).style(theme.error),
+            None => Paragraph::new("[q] quit  [r] reload  [s/Esc] back to entries"),
+        };
+        frame.render_widget(
+            footer.block(Block::default().borders(Borders::ALL)),
+            chunks[2],
+        );
+    }
+
+    fn handle_action(&mut self, action: Action, repo: &mut dyn EntryRepository) -> ScreenResult {
+        match action {
+            Action::Quit => ScreenResult::Quit,
+            Action::Logout => ScreenResult::Logout,
+            Action::InputChar('r') => {
+                self.reload(repo);
+                ScreenResult::None
+            }
+            Action::InputChar('s') | Action::Cancel => ScreenResult::Back,
+            _ => ScreenResult::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::{Category, NewEntry};
+    use storage::SqliteRepository;
+
+    #[test]
+    fn shows_only_the_selected_month() {
+        let mut repo = SqliteRepository::new_in_memory().expect("repo created");
+        for (category, cents, month) in [("food", 1200, 5), ("rent", 90000, 5), ("food", 700, 6)] {
+            repo.add(NewEntry {
+                kind: EntryKind::Expense,
+                amount: Money::from_minor(cents, iso::USD),
+                category: Category::new(category).unwrap(),
+                note: None,
+                occurred_on: NaiveDate::from_ymd_opt(2024, month, 10).expect("date"),
+                account: None,
+                tags: Vec::new(),
+            })
+            .expect("entry added");
+        }
+
+        let mut screen = StatsScreen::new();
+        screen.month = NaiveDate::from_ymd_opt(2024, 5, 1).expect("date");
+        screen.handle_action(Action::InputChar('r'), &mut repo);
+        let rows: Vec<(&str, i64)> = screen
+            .breakdown
+            .iter()
+            .map(|row| (row.category.as_str(), row.total_cents))
+            .collect();
+        assert_eq!(rows, [("rent", 90000), ("food", 1200)]);
+
+        let result = screen.handle_action(Action::InputChar('s'), &mut repo);
+        assert!(matches!(result, ScreenResult::Back));
+    }
+}
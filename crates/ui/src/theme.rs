@@ -0,0 +1,110 @@
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::toast::ToastLevel;
+
+/// The styles every screen draws with. `App` owns the active theme and
+/// hands it to each screen's `render`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Border of the login form.
+    pub border: Style,
+    /// The focused field, button or dropdown row in forms.
+    pub focus: Style,
+    /// Form fields without focus.
+    pub field: Style,
+    /// The focused field on the login screen.
+    pub highlight: Style,
+    /// The focused button on the login screen.
+    pub button: Style,
+    /// Login fields and buttons without focus.
+    pub inactive: Style,
+    pub income: Style,
+    pub expense: Style,
+    pub error: Style,
+    /// Hints that are not errors, like the first-run message.
+    pub info: Style,
+    /// Secondary text such as the running balance.
+    pub muted: Style,
+    /// Background of dropdowns drawn over a form.
+    pub popup: Style,
+    pub toast_info: Style,
+    pub toast_success: Style,
+    pub toast_error: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let toast = Style::default().fg(Color::Black);
+        Self {
+            border: Style::default().fg(Color::Cyan),
+            focus: Style::default().fg(Color::Black).bg(Color::White),
+            field: Style::default().fg(Color::White),
+            highlight: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            button: Style::default()
+                .fg(Color::White)
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+            inactive: Style::default().fg(Color::Gray),
+            income: Style::default().fg(Color::Green),
+            expense: Style::default().fg(Color::Red),
+            error: Style::default().fg(Color::Red),
+            info: Style::default().fg(Color::Cyan),
+            muted: Style::default().fg(Color::DarkGray),
+            popup: Style::default().bg(Color::DarkGray),
+            toast_info: toast.bg(Color::Cyan),
+            toast_success: toast.bg(Color::Green),
+            toast_error: toast.bg(Color::Red),
+        }
+    }
+}
+
+impl Theme {
+    /// No colors at all, only bold/reversed/dim, for terminals without color
+    /// support (or users who prefer it).
+    pub fn monochrome() -> Self {
+        let plain = Style::default();
+        let reversed = plain.add_modifier(Modifier::REVERSED);
+        let bold = plain.add_modifier(Modifier::BOLD);
+        Self {
+            border: plain,
+            focus: reversed,
+            field: plain,
+            highlight: bold,
+            button: reversed.add_modifier(Modifier::BOLD),
+            inactive: plain,
+            income: plain,
+            expense: plain,
+            error: bold,
+            info: plain,
+            muted: plain.add_modifier(Modifier::DIM),
+            popup: plain,
+            toast_info: reversed,
+            toast_success: reversed,
+            toast_error: reversed.add_modifier(Modifier::BOLD),
+        }
+    }
+
+    /// Looks up a theme by name (`"default"` or `"monochrome"`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "default" => Some(Self::default()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    pub fn toast(&self, level: ToastLevel) -> Style {
+        match level {
+            ToastLevel::Info => self.toast_info,
+            ToastLevel::Success => self.toast_success,
+            ToastLevel::Error => self.toast_error,
+        }
+    }
+
+    /// Income style for non-negative amounts, expense style otherwise.
+    pub fn amount(&self, negative: bool) -> Style {
+        if negative { self.expense } else { self.income }
+    }
+}
@@ -0,0 +1,57 @@
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::widgets::{Clear, Paragraph};
+
+use crate::theme::Theme;
+
+/// How many frames a toast stays up; the UI redraws roughly every 100ms.
+pub const TOAST_FRAMES: u16 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Success,
+    Error,
+}
+
+/// A transient message drawn over the bottom row of any screen.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    message: String,
+    level: ToastLevel,
+    frames_left: u16,
+}
+
+impl Toast {
+    pub fn new(message: impl Into<String>, level: ToastLevel) -> Self {
+        Self {
+            message: message.into(),
+            level,
+            frames_left: TOAST_FRAMES,
+        }
+    }
+
+    /// Draws the toast and counts the frame; returns `false` once it expired.
+    pub fn render(&mut self, frame: &mut Frame<'_>, theme: &Theme) -> bool {
+        if self.frames_left == 0 {
+            return false;
+        }
+        self.frames_left -= 1;
+
+        let area = frame.area();
+        if area.height == 0 {
+            return true;
+        }
+        let row = Rect {
+            y: area.bottom() - 1,
+            height: 1,
+            ..area
+        };
+        frame.render_widget(Clear, row);
+        frame.render_widget(
+            Paragraph::new(format!(" {} ", self.message)).style(theme.toast(self.level)),
+            row,
+        );
+        true
+    }
+}
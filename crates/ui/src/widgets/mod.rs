@@ -1 +1,4 @@
 // Reusable widgets live here.
+mod text_input;
+
+pub use text_input::{TextInput, first_line};
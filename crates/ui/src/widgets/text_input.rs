@@ -0,0 +1,429 @@
+use std::borrow::Cow;
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+
+use crate::event::Action;
+
+/// Editable text buffer backing the form fields.
+///
+/// Edits happen at the cursor, which `NavLeft`/`NavRight` move one character
+/// at a time (and `NavUp`/`NavDown` one line, in multi-line mode). In
+/// multi-line mode `Enter` is expected to insert a newline instead of
+/// submitting, so screens route `Action::Activate` to `insert_newline`.
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    value: String,
+    /// Byte offset into `value`, always on a char boundary.
+    cursor: usize,
+    multiline: bool,
+    masked: bool,
+    /// Temporarily shows the characters of a masked input.
+    revealed: bool,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn multiline() -> Self {
+        Self {
+            multiline: true,
+            ..Self::default()
+        }
+    }
+
+    /// A single-line input rendered as `*` characters, for passwords.
+    pub fn masked() -> Self {
+        Self {
+            masked: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Replaces the contents and moves the cursor to the end.
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.len();
+    }
+
+    /// Shows (or masks again) the characters of a masked input; has no effect
+    /// on plain inputs.
+    pub fn set_revealed(&mut self, revealed: bool) {
+        self.revealed = revealed && self.masked;
+    }
+
+    pub fn is_revealed(&self) -> bool {
+        self.revealed
+    }
+
+    pub fn insert_char(&mut self, ch: char) {
+        self.value.insert(self.cursor, ch);
+        self.cursor += ch.len_utf8();
+    }
+
+    /// Inserts pasted text at the cursor. Control characters are dropped,
+    /// except line breaks in multi-line inputs.
+    pub fn insert_str(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n");
+        for ch in text.chars() {
+            if !ch.is_control() || (ch == '\n' && self.multiline) {
+                self.insert_char(ch);
+            }
+        }
+    }
+
+    /// Inserts a line break; returns `false` for single-line inputs.
+    pub fn insert_newline(&mut self) -> bool {
+        if !self.multiline {
+            return false;
+        }
+        self.insert_char('\n');
+        true
+    }
+
+    /// Deletes the character before the cursor.
+    pub fn backspace(&mut self) {
+        if let Some(ch) = self.value[..self.cursor].chars().next_back() {
+            self.cursor -= ch.len_utf8();
+            self.value.remove(self.cursor);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        if let Some(ch) = self.value[..self.cursor].chars().next_back() {
+            self.cursor -= ch.len_utf8();
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if let Some(ch) = self.value[self.cursor..].chars().next() {
+            self.cursor += ch.len_utf8();
+        }
+    }
+
+    /// Moves to the start of the current line (the whole value for
+    /// single-line inputs).
+    pub fn move_line_start(&mut self) {
+        self.cursor = self.value[..self.cursor]
+            .rfind('\n')
+            .map_or(0, |idx| idx + 1);
+    }
+
+    pub fn move_line_end(&mut self) {
+        self.cursor = self.value[self.cursor..]
+            .find('\n')
+            .map_or(self.value.len(), |idx| self.cursor + idx);
+    }
+
+    /// Moves to the previous line, keeping the column where possible.
+    /// Returns `false` on the first line (or for single-line inputs).
+    pub fn move_up(&mut self) -> bool {
+        let start = self.line_start();
+        if start == 0 {
+            return false;
+        }
+        let column = self.value[start..self.cursor].chars().count();
+        let prev_start = self.value[..start - 1].rfind('\n').map_or(0, |idx| idx + 1);
+        self.cursor = self.offset_in_line(prev_start, column);
+        true
+    }
+
+    /// Moves to the next line, keeping the column where possible. Returns
+    /// `false` on the last line (or for single-line inputs).
+    pub fn move_down(&mut self) -> bool {
+        let Some(end) = self.value[self.cursor..]
+            .find('\n')
+            .map(|idx| self.cursor + idx)
+        else {
+            return false;
+        };
+        let column = self.value[self.line_start()..self.cursor].chars().count();
+        self.cursor = self.offset_in_line(end + 1, column);
+        true
+    }
+
+    fn line_start(&self) -> usize {
+        self.value[..self.cursor]
+            .rfind('\n')
+            .map_or(0, |idx| idx + 1)
+    }
+
+    /// Byte offset of `column` chars into the line starting at `start`,
+    /// clamped to the end of that line.
+    fn offset_in_line(&self, start: usize, column: usize) -> usize {
+        self.value[start..]
+            .char_indices()
+            .take_while(|(_, ch)| *ch != '\n')
+            .nth(column)
+            .map_or_else(
+                || {
+                    self.value[start..]
+                        .find('\n')
+                        .map_or(self.value.len(), |idx| start + idx)
+                },
+                |(idx, _)| start + idx,
+            )
+    }
+
+    /// Applies an editing action; returns `false` if the action is not one
+    /// the input understands, so the caller can handle it instead.
+    pub fn handle_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::InputChar(ch) => self.insert_char(ch),
+            Action::Paste(text) => self.insert_str(&text),
+            Action::Backspace => self.backspace(),
+            Action::NavLeft => self.move_left(),
+            Action::NavRight => self.move_right(),
+            Action::NavUp => return self.move_up(),
+            Action::NavDown => return self.move_down(),
+            Action::LineStart => self.move_line_start(),
+            Action::LineEnd => self.move_line_end(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Renders the value as styled lines, one per `\n`-separated line. When
+    /// `show_caret` is set the character under the cursor is reversed.
+    pub fn lines(&self, style: Style, show_caret: bool) -> Vec<Line<'static>> {
+        let masked = self.masked && !self.revealed;
+        let caret_style = style.add_modifier(Modifier::REVERSED);
+        let mut lines = Vec::new();
+        let mut spans = Vec::new();
+        let mut text = String::new();
+
+        for (idx, ch) in self.value.char_indices() {
+            let at_caret = show_caret && idx == self.cursor;
+            if at_caret || ch == '\n' {
+                if !text.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut text), style));
+                }
+                if at_caret {
+                    let shown = match ch {
+                        '\n' => ' ',
+                        _ if masked => '*',
+                        _ => ch,
+                    };
+                    spans.push(Span::styled(shown.to_string(), caret_style));
+                }
+                if ch == '\n' {
+                    lines.push(Line::from(std::mem::take(&mut spans)));
+                }
+                continue;
+            }
+            text.push(if masked { '*' } else { ch });
+        }
+
+        if !text.is_empty() {
+            spans.push(Span::styled(text, style));
+        }
+        if show_caret && self.cursor == self.value.len() {
+            spans.push(Span::styled(" ", caret_style));
+        }
+        lines.push(Line::from(spans));
+        lines
+    }
+
+    /// Single-line rendering wrapped in `[ ]`, as used by the forms.
+    pub fn bracketed_line(&self, style: Style, show_caret: bool) -> Line<'static> {
+        let mut spans = vec![Span::styled("[ ", style)];
+        if let Some(line) = self.lines(style, show_caret).into_iter().next() {
+            spans.extend(line.spans);
+        }
+        spans.push(Span::styled(" ]", style));
+        Line::from(spans)
+    }
+}
+
+/// Returns the first line of `text`, used where only one row is available.
+/// An ellipsis marks that more lines follow.
+pub fn first_line(text: &str) -> Cow<'_, str> {
+    match text.split_once('\n') {
+        Some((first, _)) => Cow::Owned(format!("{}…", first.trim_end_matches('\r'))),
+        None => Cow::Borrowed(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_text(line: &Line<'_>) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn multiline_input_inserts_newlines() {
+        let mut input = TextInput::multiline();
+        for ch in "rent".chars() {
+            input.insert_char(ch);
+        }
+        assert!(input.insert_newline());
+        for ch in "march".chars() {
+            input.insert_char(ch);
+        }
+
+        assert_eq!(input.value(), "rent\nmarch");
+    }
+
+    #[test]
+    fn paste_inserts_at_cursor_without_control_chars() {
+        let mut input = TextInput::new();
+        input.set_value("ab");
+        input.move_left();
+        assert!(input.handle_action(Action::Paste("x\ty\r\nz\u{7}".to_string())));
+        assert_eq!(input.value(), "axyzb");
+
+        let mut note = TextInput::multiline();
+        note.handle_action(Action::Paste("lunch\r\nwith team".to_string()));
+        assert_eq!(note.value(), "lunch\nwith team");
+    }
+
+    #[test]
+    fn single_line_input_ignores_newlines() {
+        let mut input = TextInput::new();
+        input.insert_char('a');
+        assert!(!input.insert_newline());
+        assert_eq!(input.value(), "a");
+    }
+
+    #[test]
+    fn edits_happen_at_cursor() {
+        let mut input = TextInput::new();
+        input.set_value("alce");
+        input.move_left();
+        input.move_left();
+        input.insert_char('i');
+        assert_eq!(input.value(), "alice");
+
+        input.move_right();
+        input.backspace();
+        assert_eq!(input.value(), "alie");
+
+        for _ in 0..10 {
+            input.move_left();
+        }
+        input.backspace();
+        input.insert_char('A');
+        assert_eq!(input.value(), "Aalie");
+    }
+
+    #[test]
+    fn home_and_end_jump_within_line() {
+        let mut input = TextInput::masked();
+        for ch in "hunter2".chars() {
+            input.handle_action(Action::InputChar(ch));
+        }
+        input.handle_action(Action::LineStart);
+        input.handle_action(Action::InputChar('>'));
+        input.handle_action(Action::LineEnd);
+        input.handle_action(Action::InputChar('<'));
+        assert_eq!(input.value(), ">hunter2<");
+
+        let mut note = TextInput::multiline();
+        note.set_value("first\nsecond");
+        note.handle_action(Action::LineStart);
+        note.insert_char('-');
+        note.handle_action(Action::LineEnd);
+        note.insert_char('.');
+        assert_eq!(note.value(), "first\n-second.");
+    }
+
+    #[test]
+    fn cursor_respects_multibyte_chars() {
+        let mut input = TextInput::new();
+        input.set_value("café");
+        input.move_left();
+        input.backspace();
+        input.move_right();
+        input.insert_char('!');
+        assert_eq!(input.value(), "caé!");
+    }
+
+    #[test]
+    fn masked_input_renders_caret_in_place() {
+        let mut input = TextInput::masked();
+        input.set_value("secret");
+        input.move_left();
+        input.move_left();
+
+        let lines = input.lines(Style::default(), true);
+        assert_eq!(line_text(&lines[0]), "******");
+        let caret = lines[0]
+            .spans
+            .iter()
+            .position(|span| span.style.add_modifier.contains(Modifier::REVERSED))
+            .expect("caret span");
+        assert_eq!(
+            line_text(&Line::from(lines[0].spans[..caret].to_vec())),
+            "****"
+        );
+    }
+
+    #[test]
+    fn revealed_input_renders_plain_text() {
+        let mut input = TextInput::masked();
+        input.set_value("pa55word");
+        input.set_revealed(true);
+        assert_eq!(
+            line_text(&input.lines(Style::default(), false)[0]),
+            "pa55word"
+        );
+        input.set_revealed(false);
+        assert_eq!(
+            line_text(&input.lines(Style::default(), false)[0]),
+            "********"
+        );
+
+        let mut plain = TextInput::new();
+        plain.set_revealed(true);
+        assert!(!plain.is_revealed());
+    }
+
+    #[test]
+    fn up_and_down_move_between_lines() {
+        let mut note = TextInput::multiline();
+        note.set_value("groceries\nmilk\nbread and eggs");
+        note.move_line_start();
+        for _ in 0..3 {
+            note.move_right();
+        }
+
+        assert!(note.move_up());
+        note.insert_char('^');
+        assert_eq!(note.value(), "groceries\nmil^k\nbread and eggs");
+        assert!(note.move_down());
+        note.insert_char('_');
+        assert_eq!(note.value(), "groceries\nmil^k\nbrea_d and eggs");
+
+        // Short lines clamp the column to their end.
+        note.set_value("long first line\nab");
+        assert!(note.move_up());
+        assert!(note.move_down());
+        note.insert_char('!');
+        assert_eq!(note.value(), "long first line\nab!");
+        assert!(!note.move_down());
+
+        let mut single = TextInput::new();
+        single.set_value("abc");
+        assert!(!single.move_up());
+        assert!(!single.handle_action(Action::NavDown));
+    }
+
+    #[test]
+    fn first_line_truncates_multiline_text() {
+        assert_eq!(first_line("lunch\nwith team"), "lunch…");
+        assert_eq!(first_line("lunch\r\nwith team"), "lunch…");
+        assert_eq!(first_line("lunch"), "lunch");
+        assert_eq!(first_line(""), "");
+    }
+}